@@ -312,6 +312,7 @@ async fn write_statement(
         id,
         script: script.to_string(),
         timeout: timeout.as_secs() as u32,
+        args: Args::new(),
     });
     writer_framed.send(req).await?;
     Ok(())
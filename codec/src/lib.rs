@@ -4,7 +4,7 @@ use bytes::BytesMut;
 use std::io::Read;
 use std::{convert::TryFrom, io::Cursor};
 
-pub use bee_core::{code, DataType, Error, Result, ToType, Value};
+pub use bee_core::{code, Args, DataType, Error, Result, ToType, Value};
 pub use connect::{ConnectionReq, ConnectionReqCodec, ConnectionResp, ConnectionRespCodec};
 pub use statement::{
     StatementReq, StatementReqCodec, StatementResp, StatementRespCodec, StatementStateResp,
@@ -422,6 +422,7 @@ mod test {
             id: 01,
             script: "SELECT *FROM m_test()".to_owned(),
             timeout: 10,
+            args: crate::Args::new(),
         });
         let packet = req;
         let mut dist = BytesMut::new();
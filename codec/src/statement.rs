@@ -2,7 +2,7 @@ use crate::{
     read_error, read_src_value, read_value, write_error, write_value, TypeSize, SPACE_BYTE,
 };
 use bee_core::Error;
-use bee_core::{Columns, DataType, Result, Row, State, Value};
+use bee_core::{Args, Columns, DataType, Result, Row, State, Value};
 use bytes::Buf;
 use bytes::BufMut;
 use bytes::BytesMut;
@@ -14,6 +14,8 @@ pub struct StatementReq {
     pub id: u32,
     pub script: String,
     pub timeout: u32,
+    /// 绑定到脚本的参数列表
+    pub args: Args,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -89,11 +91,20 @@ impl Decoder for StatementReqCodec {
         let id: u32 = read_value(&mut buf)?;
         let script: String = read_value(&mut buf)?;
         let timeout: u32 = read_value(&mut buf)?;
+        // 参数列表位于末尾，没有参数时不发送
+        let mut args = Args::new();
+        if buf.has_remaining() {
+            let len: u32 = read_value(&mut buf)?;
+            for _ in 0..len {
+                args.push(read_src_value(&mut buf)?);
+            }
+        }
 
         Ok(Some(StatementReq {
             id,
             script,
             timeout,
+            args,
         }))
     }
 }
@@ -104,6 +115,12 @@ impl Encoder<StatementReq> for StatementReqCodec {
         write_value(item.id, dst);
         write_value(item.script, dst);
         write_value(item.timeout, dst);
+        if !item.args.is_empty() {
+            write_value(item.args.len() as u32, dst);
+            for value in item.args.iter() {
+                write_value(value.clone(), dst);
+            }
+        }
 
         Ok(())
     }
@@ -203,7 +220,7 @@ mod test {
     use super::{
         StatementReq, StatementReqCodec, StatementResp, StatementRespCodec, StatementStateResp,
     };
-    use bee_core::{columns, row, Error, Row};
+    use bee_core::{args, columns, row, Args, Error, Row};
     use bytes::BytesMut;
     use tokio_util::codec::{Decoder, Encoder};
 
@@ -219,6 +236,7 @@ mod test {
             id: 01,
             script: "SELECT *FROM m_test()".to_owned(),
             timeout: 10,
+            args: Args::new(),
         };
         let mut dist = BytesMut::new();
         codec.encode(req.clone(), &mut dist).unwrap();
@@ -233,6 +251,26 @@ mod test {
         assert_eq!(req.id, rs.id);
         assert_eq!(req.script, rs.script);
         assert_eq!(req.timeout, rs.timeout);
+        assert!(rs.args.is_empty());
+    }
+
+    #[test]
+    fn test_req_args() {
+        let mut codec = StatementReqCodec;
+        let req = StatementReq {
+            id: 01,
+            script: "SELECT * FROM shell(?, 10)".to_owned(),
+            timeout: 10,
+            args: args!["echo 'Hello'", 2, ()],
+        };
+        let mut dist = BytesMut::new();
+        codec.encode(req.clone(), &mut dist).unwrap();
+        assert!(dist.ends_with(
+            b"\x02\x00\x00\x00\x00\x00\x00\x00\x0A\x02\x00\x00\x00\x00\x00\x00\x00\x03\x01\x00\x00\x00\x0C\x65\x63\x68\x6F\x20\x27\x48\x65\x6C\x6C\x6F\x27\x02\x00\x00\x00\x00\x00\x00\x00\x02\x00"
+        ));
+
+        let rs = codec.decode(&mut dist).unwrap().unwrap();
+        assert_eq!(req, rs);
     }
 
     #[test]
@@ -15,7 +15,7 @@ pub fn impl_to_data(input: DeriveInput) -> TokenStream {
 
                     let name_str = quote! {#ident}.to_string();
                     quote_spanned! {field.span() =>
-                        #type_::get_type() => #name_str
+                        <#type_>::get_type() => #name_str
                     }
                 });
 
@@ -1,9 +1,10 @@
 use proc_macro::TokenStream;
 use quote::{quote, quote_spanned, ToTokens};
 use syn::spanned::Spanned;
-use syn::{parse_macro_input, Ident, ItemFn, PatType, Type};
+use syn::{parse_macro_input, AttributeArgs, Ident, ItemFn, Meta, NestedMeta, PatType, Type};
 
-pub fn impl_datasource(_: TokenStream, input: TokenStream) -> TokenStream {
+pub fn impl_datasource(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let attr = parse_macro_input!(attr as AttributeArgs);
     let input = parse_macro_input!(input as ItemFn);
     let name = &input.sig.ident;
     let define = &input.to_token_stream();
@@ -33,6 +34,8 @@ pub fn impl_datasource(_: TokenStream, input: TokenStream) -> TokenStream {
     let t = match_promise_t(typed);
     // 获取 函数名称(字符串类型) 用作于 DS 的名称
     let name_str = quote! {#name}.to_string();
+    // 获取可以下推过滤条件的列
+    let filter_columns = match_filter_columns(&attr);
     // 获取 Promise 的构建代码
     let promise_body = quote! {
         let mut promise: Promise<#t> = request.head()?;
@@ -67,6 +70,7 @@ pub fn impl_datasource(_: TokenStream, input: TokenStream) -> TokenStream {
             fn get_register(&self) -> &crate::Register{
                 &self.register
             }
+            #filter_columns
             fn collect(&self, request: &mut crate::Request) -> crate::Result<()> {
                 #promise_body
                 #call_args_body
@@ -78,6 +82,33 @@ pub fn impl_datasource(_: TokenStream, input: TokenStream) -> TokenStream {
     TokenStream::from(quote!(#data_source_impl))
 }
 
+/// 获取 `#[datasource(filter(col1, col2))]` 中声明的过滤列
+fn match_filter_columns(attr: &AttributeArgs) -> proc_macro2::TokenStream {
+    let mut columns = vec![];
+    for meta in attr {
+        if let NestedMeta::Meta(Meta::List(list)) = meta {
+            if !list.path.is_ident("filter") {
+                continue;
+            }
+            for nested in list.nested.iter() {
+                if let NestedMeta::Meta(Meta::Path(path)) = nested {
+                    if let Some(ident) = path.get_ident() {
+                        columns.push(ident.to_string());
+                    }
+                }
+            }
+        }
+    }
+    if columns.is_empty() {
+        return quote! {};
+    }
+    quote! {
+        fn filter_columns(&self) -> Vec<&str> {
+            vec![#(#columns),*]
+        }
+    }
+}
+
 /// 查找 Promise 参数类型
 fn look_promise<'a>(args: &'a Vec<(&Box<Type>, String, &PatType)>) -> &'a Box<Type> {
     args.iter()
@@ -119,6 +150,22 @@ fn match_promise_t(type_: &Box<Type>) -> proc_macro2::TokenStream {
     }
 }
 
+/// 获取 Option 范型 T 类型，非 Option 类型时返回 None
+fn match_option_t(type_: &Box<Type>) -> Option<&Type> {
+    if let Type::Path(ref token) = **type_ {
+        let segment = token.path.segments.last()?;
+        if segment.ident != "Option" {
+            return None;
+        }
+        if let syn::PathArguments::AngleBracketed(ref angle) = segment.arguments {
+            if let Some(syn::GenericArgument::Type(ref typed)) = angle.args.first() {
+                return Some(typed);
+            }
+        }
+    }
+    None
+}
+
 /// 获取 DS 输入参数列表
 fn match_ds_args(args: &Vec<(&Box<Type>, String, &PatType)>) -> proc_macro2::TokenStream {
     let args = args
@@ -140,8 +187,14 @@ fn match_ds_args(args: &Vec<(&Box<Type>, String, &PatType)>) -> proc_macro2::Tok
             let typed = arg.2;
             let ident = &arg.1;
             let type_ = arg.0;
-            quote_spanned! {typed.span() =>
-                #type_::get_type() => #ident
+            // 可选参数使用其内部类型作为列类型
+            match match_option_t(type_) {
+                Some(inner) => quote_spanned! {typed.span() =>
+                    <#inner>::get_type() => #ident
+                },
+                None => quote_spanned! {typed.span() =>
+                    <#type_>::get_type() => #ident
+                },
             }
         });
     quote! {
@@ -166,6 +219,13 @@ fn match_call_args(
             quote_spanned! {typed.span()=>
                 &mut promise
             }
+        } else if let Some(inner) = match_option_t(type_) {
+            let token = quote_spanned! {typed.span()=>
+                request.get_args().get_option::<#inner>(#index)?
+            };
+            index += 1;
+
+            token
         } else {
             let token = quote_spanned! {typed.span()=>
                 request.get_args().get::<#type_>(#index)?
@@ -1,8 +1,8 @@
 use crate::{Error, Result, Value};
-use std::convert::TryFrom;
+use std::{convert::TryFrom, ops::Deref};
 
 /// 输入参数列表
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Args {
     values: Vec<Value>,
 }
@@ -22,6 +22,14 @@ impl Args {
             .ok_or(Error::index_range(index))?
     }
 
+    /// 获取可选参数值，参数不存在或为空值时返回 `None`
+    pub fn get_option<T: TryFrom<Value, Error = Error>>(&self, index: usize) -> Result<Option<T>> {
+        match self.values.get(index) {
+            None | Some(Value::Nil) => Ok(None),
+            Some(val) => T::try_from(val.clone()).map(Some),
+        }
+    }
+
     /// 添加一个参数值
     #[inline(always)]
     pub fn push<T: Into<Value>>(&mut self, value: T) {
@@ -29,6 +37,20 @@ impl Args {
     }
 }
 
+impl Deref for Args {
+    type Target = Vec<Value>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.values
+    }
+}
+
+impl AsRef<Vec<Value>> for Args {
+    fn as_ref(&self) -> &Vec<Value> {
+        &self.values
+    }
+}
+
 impl Default for Args {
     fn default() -> Self {
         Self::new()
@@ -54,9 +76,21 @@ fn test() {
     let val1 = args.get::<Vec<u8>>(4).unwrap();
     assert_eq!(vec![0x01, 0x02], val1);
 
+    assert_eq!(5, args.len());
+    assert_eq!(
+        crate::args!(10, 20.0, "Name", false, vec![0x01, 0x02]),
+        args
+    );
+
     let mut args: Args = Args::new();
     args.push(10);
     assert_eq!(10, args.get(0).unwrap());
     assert!(args.get::<f64>(0).is_err());
     assert!(args.get::<u32>(1).is_err());
+
+    let args: Args = crate::args!(10, ());
+    assert_eq!(Some(10), args.get_option::<i64>(0).unwrap());
+    assert_eq!(None, args.get_option::<i64>(1).unwrap());
+    assert_eq!(None, args.get_option::<i64>(2).unwrap());
+    assert!(args.get_option::<f64>(0).is_err());
 }
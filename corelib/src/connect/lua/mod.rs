@@ -53,12 +53,13 @@ impl Configure for LuaSession {
 }
 
 impl Connection for LuaSession {
-    fn new_statement(
+    fn new_statement_with_args(
         &self,
         script: &str,
+        args: Args,
         timeout: std::time::Duration,
     ) -> crate::Result<crate::Statement> {
-        let (mut request, response) = new_req(Args::new(), timeout);
+        let (mut request, response) = new_req(args, timeout);
         let script = script.to_string();
         let ds_list = self.ds_list.clone();
         let func_list = self.func_list.clone();
@@ -135,6 +136,9 @@ fn register_context(
         global.set(key.clone(), function)?;
     }
 
+    // 执行脚本时绑定的参数列表
+    let args = context.create_sequence_from(request.get_args().iter().cloned())?;
+    global.set("args", args)?;
     global.set("_request", request)?;
     Ok(())
 }
@@ -155,7 +159,7 @@ fn test() {
         .unwrap();
     let resp = statement.wait().unwrap();
     let cols = resp.columns();
-    assert_eq!(5, cols.len());
+    assert_eq!(11, cols.len());
 
     let mut index = 0;
     for row in resp {
@@ -200,3 +204,27 @@ fn test_runtime() {
         .unwrap();
     let _ = statement.wait().unwrap();
 }
+
+#[test]
+fn test_args() {
+    let lua_script = r#"
+        for i = 1, #args do
+            _request:commit({ value = args[i] });
+        end
+        "#;
+    let conn = crate::new_connection("lua:debug:default").unwrap();
+
+    let statement = conn
+        .new_statement_with_args(
+            lua_script,
+            crate::args!["Hello", "world"],
+            std::time::Duration::from_secs(2),
+        )
+        .unwrap();
+    let values: Vec<String> = statement
+        .wait()
+        .unwrap()
+        .map(|row| row.unwrap().get(0).unwrap())
+        .collect();
+    assert_eq!(vec!["Hello".to_owned(), "world".to_owned()], values);
+}
@@ -1,4 +1,4 @@
-use crate::{datasource, funcs, Configure, Error, Instance, Result, Statement};
+use crate::{datasource, funcs, Args, Configure, Error, Instance, Result, Statement};
 use std::time::Duration;
 
 #[cfg(feature = "lua")]
@@ -9,7 +9,19 @@ mod sqlite;
 /// 连接器
 pub trait Connection: Send + Sync {
     /// 创建结果集
-    fn new_statement(&self, script: &str, timeout: Duration) -> Result<Statement>;
+    fn new_statement(&self, script: &str, timeout: Duration) -> Result<Statement> {
+        self.new_statement_with_args(script, Args::new(), timeout)
+    }
+
+    /// 创建结果集，并绑定参数列表
+    ///
+    /// Sqlite 中参数按照顺序绑定到 `?`、`?NNN` 以及 `:name` 等参数上，Lua 中通过全局的 `args` 表获取参数 (索引从 1 开始)
+    fn new_statement_with_args(
+        &self,
+        script: &str,
+        args: Args,
+        timeout: Duration,
+    ) -> Result<Statement>;
 }
 
 pub fn new_connection(url: &str) -> Result<Box<dyn Connection>> {
@@ -28,7 +40,7 @@ pub fn new_connection(url: &str) -> Result<Box<dyn Connection>> {
 
 #[cfg(feature = "sqlite")]
 pub fn new_sqlite_connection(instance: &Instance) -> Result<Box<dyn Connection>> {
-    let connection = sqlite::SqliteSession::new()?;
+    let connection = sqlite::SqliteSession::from_instance(instance)?;
     register(instance, &connection)?;
     Ok(Box::new(connection))
 }
@@ -29,7 +29,6 @@ const UNWINDINGPANIC: i32 = code!(BASE_CODE, 18);
 const GETAUXWRONGTYPE: i32 = code!(BASE_CODE, 19);
 const INVALIDPARAMETERCOUNT: i32 = code!(BASE_CODE, 20);
 const OTHER: i32 = code!(BASE_CODE, 21);
-pub(crate) const INVALIDCOLUMNCOUNT: i32 = code!(BASE_CODE, 22);
 
 impl From<SQLiteError> for Error {
     fn from(err: SQLiteError) -> Self {
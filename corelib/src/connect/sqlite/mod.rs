@@ -2,12 +2,11 @@
 mod convert;
 mod sql_tab;
 
-use crate::Error;
 use crate::{new_req, Args, Columns, DataSource, DataType, Request, State, Statement, Value};
-use convert::INVALIDCOLUMNCOUNT;
+use crate::{Error, Instance};
 use parking_lot::ReentrantMutex;
 use rusqlite::vtab::eponymous_only_module;
-use rusqlite::{Column, Connection, OpenFlags, Result, Row, NO_PARAMS};
+use rusqlite::{Column, Connection, OpenFlags, Result, Row};
 use sql_tab::SQLTab;
 use std::panic::UnwindSafe;
 use std::{sync::Arc, time::Duration};
@@ -25,6 +24,25 @@ impl SqliteSession {
         })
     }
 
+    /// 根据实例参数创建 Sqlite 连接
+    ///
+    /// - `db`: 打开 (不存在时创建) 指定的数据库文件，可以读写
+    /// - `writable`: 未指定 `db` 时打开可读写的内存数据库
+    ///
+    /// 未指定以上参数时与 [`SqliteSession::new`] 相同，打开只读的内存数据库
+    pub fn from_instance(instance: &Instance) -> crate::Result<Self> {
+        let db: Option<String> = instance.get_param("db").ok();
+        let writable: bool = instance.get_param("writable").unwrap_or(false);
+        let connection = match db {
+            Some(path) => Self::open_connection(&path)?,
+            None if writable => Self::new_writable_connection()?,
+            None => return Ok(Self::new()?),
+        };
+        Ok(Self {
+            connection: Arc::new(ReentrantMutex::new(connection)),
+        })
+    }
+
     fn new_connection() -> Result<Connection> {
         let flag = OpenFlags::SQLITE_OPEN_READ_ONLY
             | OpenFlags::SQLITE_OPEN_NO_MUTEX
@@ -34,6 +52,27 @@ impl SqliteSession {
         conn.set_prepared_statement_cache_capacity(0);
         Ok(conn)
     }
+
+    fn new_writable_connection() -> Result<Connection> {
+        let flag = OpenFlags::SQLITE_OPEN_READ_WRITE
+            | OpenFlags::SQLITE_OPEN_CREATE
+            | OpenFlags::SQLITE_OPEN_NO_MUTEX
+            | OpenFlags::SQLITE_OPEN_MEMORY
+            | OpenFlags::SQLITE_OPEN_URI;
+        let conn = Connection::open_in_memory_with_flags(flag)?;
+        conn.set_prepared_statement_cache_capacity(0);
+        Ok(conn)
+    }
+
+    fn open_connection(path: &str) -> Result<Connection> {
+        let flag = OpenFlags::SQLITE_OPEN_READ_WRITE
+            | OpenFlags::SQLITE_OPEN_CREATE
+            | OpenFlags::SQLITE_OPEN_NO_MUTEX
+            | OpenFlags::SQLITE_OPEN_URI;
+        let conn = Connection::open_with_flags(path, flag)?;
+        conn.set_prepared_statement_cache_capacity(0);
+        Ok(conn)
+    }
 }
 
 impl crate::Configure for SqliteSession {
@@ -79,8 +118,13 @@ impl crate::Configure for SqliteSession {
 }
 
 impl crate::Connection for SqliteSession {
-    fn new_statement(&self, script: &str, timeout: Duration) -> crate::Result<Statement> {
-        let (request, response) = new_req(Args::new(), timeout);
+    fn new_statement_with_args(
+        &self,
+        script: &str,
+        args: Args,
+        timeout: Duration,
+    ) -> crate::Result<Statement> {
+        let (request, response) = new_req(args, timeout);
         let conn = self.connection.clone();
 
         let script = script.to_string();
@@ -96,7 +140,7 @@ impl crate::Connection for SqliteSession {
     }
 }
 
-/// 提交一个请求，并执行
+/// 提交一个请求，并执行，请求的参数列表按照顺序绑定到语句的参数上
 fn commit_statement(
     db: Arc<ReentrantMutex<Connection>>,
     script: String,
@@ -104,7 +148,9 @@ fn commit_statement(
 ) -> Result<(), Error> {
     let lock = db.lock();
     let mut s = lock.prepare(script.as_str())?;
-    let mut rows = s.query(NO_PARAMS)?;
+    // 获取声明的列结构，第一行数据中存在空值时，仍可以确定列的类型
+    let decl_columns = get_columns(s.columns());
+    let mut rows = s.query(request.get_args().iter())?;
 
     // 需要先发送列的结构定义
     // 尝试获取一行数据，才能决定列的类型
@@ -117,21 +163,19 @@ fn commit_statement(
                 let name = row.column_name(i)?;
                 let value = row.get::<usize, Value>(i)?;
 
-                cols.push(name, DataType::from(value));
+                let t = match decl_columns.get(i) {
+                    Some((_, t)) if *t != DataType::Nil => *t,
+                    _ => DataType::from(value),
+                };
+                cols.push(name, t);
             }
 
             let mut promise = request.new_commit(cols)?;
             promise.commit(State::from(new_row))?;
             promise
         }
-        None => {
-            // 获取默认的列结构
-            let sql_columns = rows.columns().ok_or(Error::invalid(
-                INVALIDCOLUMNCOUNT,
-                format!("can't find columns"),
-            ))?;
-            request.new_commit(get_columns(sql_columns))?
-        }
+        // 没有数据行 (包括 `CREATE`、`INSERT` 等不返回结果的语句) 时使用声明的列结构
+        None => request.new_commit(decl_columns)?,
     };
 
     // 循环发送数据
@@ -160,17 +204,7 @@ fn get_columns(sql_columns: Vec<Column>) -> Columns {
         let sql_type: Option<&str> = col.decl_type();
 
         let t = match sql_type {
-            Some(t) => {
-                let t = t.to_uppercase();
-                let t = t.as_str();
-                match t {
-                    "TEXT" => DataType::String,
-                    "INTEGER" => DataType::Integer,
-                    "REAL" => DataType::Number,
-                    "BLOB" => DataType::Bytes,
-                    _ => DataType::Number,
-                }
-            }
+            Some(t) => decl_type(t),
             None => DataType::Nil,
         };
         columns.push(name, t);
@@ -179,6 +213,24 @@ fn get_columns(sql_columns: Vec<Column>) -> Columns {
     columns
 }
 
+/// 按照 Sqlite 的类型亲和性规则转换声明的类型，例如 `VARCHAR(64)` 为 String，`BIGINT` 为 Integer
+///
+/// NUMERIC 亲和性的类型 (例如 `DATETIME`、`BOOLEAN`) 返回 `Nil`，由实际的值决定列的类型
+fn decl_type(decl: &str) -> DataType {
+    let decl = decl.to_uppercase();
+    if decl.contains("INT") {
+        DataType::Integer
+    } else if decl.contains("CHAR") || decl.contains("CLOB") || decl.contains("TEXT") {
+        DataType::String
+    } else if decl.contains("BLOB") {
+        DataType::Bytes
+    } else if decl.contains("REAL") || decl.contains("FLOA") || decl.contains("DOUB") {
+        DataType::Number
+    } else {
+        DataType::Nil
+    }
+}
+
 #[cfg(test)]
 pub fn init_log() {
     let _ = env_logger::builder()
@@ -200,7 +252,7 @@ fn test_sqlite_sql() {
         .unwrap();
     let resp = statement.wait().unwrap();
     let cols = resp.columns();
-    assert_eq!(5, cols.len());
+    assert_eq!(11, cols.len());
 
     let mut index = 0;
     for row in resp {
@@ -250,3 +302,109 @@ fn test_faild_not_such_func() {
         .unwrap();
     let _ = statement.wait().unwrap();
 }
+
+#[test]
+fn test_writable() {
+    let query = |conn: &Box<dyn crate::Connection>, sql: &str| -> Vec<i64> {
+        let statement = conn.new_statement(sql, Duration::from_secs(2)).unwrap();
+        statement
+            .wait()
+            .unwrap()
+            .map(|row| row.unwrap().get::<i64>(0).unwrap())
+            .collect()
+    };
+
+    // 默认为只读的内存数据库
+    let conn = crate::new_connection("sqlite:debug:default").unwrap();
+    let statement = conn
+        .new_statement("CREATE TABLE cpu(idle INTEGER)", Duration::from_secs(2))
+        .unwrap();
+    assert!(statement.wait().is_err());
+
+    // 同一个连接中的多次执行可以共享临时表
+    let conn = crate::new_connection("sqlite:debug:default?writable=true").unwrap();
+    query(&conn, "CREATE TEMP TABLE cpu(idle INTEGER)");
+    query(&conn, "INSERT INTO cpu VALUES (10), (20)");
+    assert_eq!(vec![30], query(&conn, "SELECT sum(idle) FROM cpu"));
+
+    // 数据库文件中的数据可以在连接之间保留
+    let path = std::env::temp_dir().join("bee_test_writable.sqlite");
+    let _ = std::fs::remove_file(&path);
+    let url = format!("sqlite:debug:default?db={}", path.display());
+    let conn = crate::new_connection(&url).unwrap();
+    query(&conn, "CREATE TABLE baseline(value INTEGER)");
+    query(
+        &conn,
+        "INSERT INTO baseline SELECT line_num FROM shell('a\nb', 10)",
+    );
+    drop(conn);
+
+    let conn = crate::new_connection(&url).unwrap();
+    assert_eq!(vec![0, 1], query(&conn, "SELECT value FROM baseline"));
+    drop(conn);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_args() {
+    let conn = crate::new_connection("sqlite:debug:default").unwrap();
+    let statement = conn
+        .new_statement_with_args(
+            "SELECT ? + 1 AS num, :name AS name",
+            crate::args![10, "He"],
+            Duration::from_secs(2),
+        )
+        .unwrap();
+    let rows: Vec<crate::Row> = statement.wait().unwrap().map(|row| row.unwrap()).collect();
+    assert_eq!(vec![crate::row![11, "He"]], rows);
+
+    // 参数同样可以作为数据源的输入参数
+    let statement = conn
+        .new_statement_with_args(
+            "SELECT line FROM shell(?, 10) WHERE stream = 'stdout' AND line_num = ?",
+            crate::args!["a\nb", 1],
+            Duration::from_secs(2),
+        )
+        .unwrap();
+    let lines: Vec<String> = statement
+        .wait()
+        .unwrap()
+        .map(|row| row.unwrap().get(0).unwrap())
+        .collect();
+    assert_eq!(vec!["b".to_owned()], lines);
+
+    // 参数数量与语句中的参数数量不一致
+    let statement = conn
+        .new_statement_with_args("SELECT ?", crate::Args::new(), Duration::from_secs(2))
+        .unwrap();
+    assert!(statement.wait().is_err());
+}
+
+#[test]
+fn test_decl_type() {
+    assert_eq!(DataType::Integer, decl_type("BIGINT"));
+    assert_eq!(DataType::String, decl_type("varchar(64)"));
+    assert_eq!(DataType::Bytes, decl_type("BLOB"));
+    assert_eq!(DataType::Number, decl_type("DOUBLE PRECISION"));
+    assert_eq!(DataType::Nil, decl_type("DATETIME"));
+
+    let conn = crate::new_connection("sqlite:debug:default?writable=true").unwrap();
+    let query = |sql: &str| {
+        conn.new_statement(sql, Duration::from_secs(2))
+            .unwrap()
+            .wait()
+            .unwrap()
+    };
+    let _ = query("CREATE TABLE host(name VARCHAR(64), cpu_core BIGINT, boot_time DATETIME)");
+    let _ = query("INSERT INTO host VALUES ('bee', 4, 'now'), (NULL, NULL, NULL)");
+    let resp = query("SELECT * FROM host");
+    assert_eq!(
+        &crate::columns![String: "name", Integer: "cpu_core", String: "boot_time"],
+        resp.columns()
+    );
+    let rows: Vec<crate::Row> = resp.map(|row| row.unwrap()).collect();
+    assert_eq!(
+        vec![crate::row!["bee", 4, "now"], crate::row![(), (), ()]],
+        rows
+    );
+}
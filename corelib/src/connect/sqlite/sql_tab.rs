@@ -1,13 +1,20 @@
 use crate::Value;
 use crate::{new_req_none, Args, Columns, DataSource, DataType, Response, Row};
+use crate::{Filter, FilterOp, Filters};
+use parking_lot::Mutex;
 use rusqlite::ffi;
 use rusqlite::vtab::{
     Context, CreateVTab, IndexConstraintOp, IndexInfo, VTab, VTabConnection, VTabCursor, Values,
 };
 use rusqlite::{types::FromSql, Error, Result};
+use std::ffi::CStr;
 use std::marker::PhantomData;
 use std::{os::raw::c_int, sync::Arc};
 
+/// 没有任何约束时的估算代价以及行数
+const FULL_SCAN_COST: f64 = 1_000_000.0;
+const FULL_SCAN_ROWS: i64 = 1_000_000;
+
 /// 统一的 Sqlite 虚拟表定义
 #[repr(C)]
 pub struct SQLTab {
@@ -18,6 +25,46 @@ pub struct SQLTab {
     params: Columns,
     args: Option<Args>,
     offset_first_row: usize,
+    /// 可以下推过滤条件的列索引
+    filter_columns: Vec<usize>,
+    /// best_index 生成的查询计划，`idx_num` 为计划的索引
+    plans: Mutex<Vec<Plan>>,
+}
+
+/// 虚拟表的查询计划，按照顺序记录 filter 中每个参数值的用途
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Plan {
+    /// 数据源输入参数的索引
+    args: Vec<usize>,
+    /// 下推到数据源的过滤条件，包含结果列的索引以及比较操作
+    filters: Vec<(usize, FilterOp)>,
+}
+
+/// 将 Sqlite 的约束转换为可下推的比较操作
+fn to_filter_op(op: IndexConstraintOp) -> Option<FilterOp> {
+    match op {
+        IndexConstraintOp::SQLITE_INDEX_CONSTRAINT_EQ => Some(FilterOp::Eq),
+        IndexConstraintOp::SQLITE_INDEX_CONSTRAINT_GT => Some(FilterOp::Gt),
+        IndexConstraintOp::SQLITE_INDEX_CONSTRAINT_GE => Some(FilterOp::Ge),
+        IndexConstraintOp::SQLITE_INDEX_CONSTRAINT_LT => Some(FilterOp::Lt),
+        IndexConstraintOp::SQLITE_INDEX_CONSTRAINT_LE => Some(FilterOp::Le),
+        IndexConstraintOp::SQLITE_INDEX_CONSTRAINT_LIKE => Some(FilterOp::Like),
+        _ => None,
+    }
+}
+
+/// 获取约束使用的排序规则，只能在 `best_index` 中调用
+fn collation(info: &IndexInfo, constraint: usize) -> Option<String> {
+    unsafe {
+        // IndexInfo 只包含 sqlite3_index_info 的指针，rusqlite 没有提供 sqlite3_vtab_collation 的封装
+        let raw = *(info as *const IndexInfo as *const *mut ffi::sqlite3_index_info);
+        let name = ffi::sqlite3_vtab_collation(raw, constraint as c_int);
+        if name.is_null() {
+            None
+        } else {
+            Some(CStr::from_ptr(name).to_string_lossy().to_string())
+        }
+    }
 }
 
 /// 将 Bee 的列类型转换为 Sqlite 类型
@@ -89,6 +136,11 @@ unsafe impl<'vtab> VTab<'vtab> for SQLTab {
         let name = ds.name();
         let sql = to_dml_sql(name, &args, &columns);
         debug!("DML - {:?}", sql);
+        let filter_columns = ds
+            .filter_columns()
+            .into_iter()
+            .filter_map(|name| columns.get_index(name))
+            .collect();
 
         // 创建虚拟表实例
         let vtab = SQLTab {
@@ -98,38 +150,99 @@ unsafe impl<'vtab> VTab<'vtab> for SQLTab {
             cols: columns,
             params: args,
             args: None,
+            filter_columns,
+            plans: Mutex::new(vec![]),
         };
 
         Ok((sql, vtab))
     }
 
     fn best_index(&self, info: &mut IndexInfo) -> Result<()> {
-        // 记录索引号的 bit 位，格式为 `0000 1100` => 表示第二列和第三列为可过滤的参数
-        let mut params = vec![];
+        let hidden = self.cols.len();
+        let mut args: Vec<(usize, usize)> = vec![];
+        let mut filters: Vec<(usize, usize, FilterOp)> = vec![];
         for (i, constraint) in info.constraints().enumerate() {
-            // 必须为 HIDDEN 属性的列才能被检索
-            if !constraint.is_usable() {
+            if !constraint.is_usable() || constraint.column() < 0 {
                 continue;
             }
-
-            // 只允许 `=` 操作才能被列入索引
-            if constraint.operator() == IndexConstraintOp::SQLITE_INDEX_CONSTRAINT_EQ {
-                params.push((i, constraint.column()));
+            let column = constraint.column() as usize;
+            let op = constraint.operator();
+            if column >= hidden {
+                // HIDDEN 属性的参数列只允许 `=` 操作
+                let index = column - hidden;
+                if op == IndexConstraintOp::SQLITE_INDEX_CONSTRAINT_EQ
+                    && !args.iter().any(|arg| arg.1 == index)
+                {
+                    args.push((i, index));
+                }
+            } else if self.filter_columns.contains(&column) {
+                // 数据源按照 BINARY 排序规则比较，其他排序规则 (例如 `COLLATE NOCASE`) 的条件不能下推
+                let binary = collation(info, i)
+                    .map(|name| name.eq_ignore_ascii_case("BINARY"))
+                    .unwrap_or(true);
+                match to_filter_op(op) {
+                    Some(FilterOp::Like) => filters.push((i, column, FilterOp::Like)),
+                    Some(op) if binary => filters.push((i, column, op)),
+                    _ => {}
+                }
             }
         }
+        args.sort_by_key(|arg| arg.1);
 
-        // 设置索引信息
-        for index in params {
-            let mut constraint_usage = info.constraint_usage(index.0);
-            constraint_usage.set_argv_index(index.1 - (self.cols.len() as i32) + 1);
+        // 参数值按照参数的顺序排列，过滤条件的值排在参数之后
+        let mut argv_index = 0;
+        for (i, _) in args.iter() {
+            argv_index += 1;
+            let mut constraint_usage = info.constraint_usage(*i);
+            constraint_usage.set_argv_index(argv_index);
             constraint_usage.set_omit(true);
         }
+        for (i, _, _) in filters.iter() {
+            argv_index += 1;
+            // 数据源的过滤只是为了减少数据量，Sqlite 仍然需要校验结果行
+            let mut constraint_usage = info.constraint_usage(*i);
+            constraint_usage.set_argv_index(argv_index);
+            constraint_usage.set_omit(false);
+        }
+
+        // 参数可以大幅减少数据量，范围过滤以及 LIKE 的代价低于全部扫描。
+        // Sqlite 会将 `IN` 拆分为多次 `=` 并且每次都重新执行数据源，存在 `IN` 时 Sqlite 会再次调用
+        // best_index 并禁用 `IN` 的约束，因此下推 `=` 的代价需要高于不下推，避免数据源被多次执行
+        let mut cost = FULL_SCAN_COST / 1000f64.powi(args.len() as i32);
+        let mut rows = FULL_SCAN_ROWS;
+        for (_, _, op) in filters.iter() {
+            if *op == FilterOp::Eq {
+                cost *= 2.0;
+            } else {
+                cost /= 2.0;
+                rows /= 2;
+            }
+        }
+        info.set_estimated_cost(cost);
+        info.set_estimated_rows(rows);
+
+        let plan = Plan {
+            args: args.into_iter().map(|arg| arg.1).collect(),
+            filters: filters
+                .into_iter()
+                .map(|filter| (filter.1, filter.2))
+                .collect(),
+        };
+        let mut plans = self.plans.lock();
+        let idx_num = match plans.iter().position(|val| *val == plan) {
+            Some(idx_num) => idx_num,
+            None => {
+                plans.push(plan);
+                plans.len() - 1
+            }
+        };
+        info.set_idx_num(idx_num as c_int);
         Ok(())
     }
 
     fn open(&self) -> Result<SQLTabCursor<'_>> {
         // 创建游标
-        Ok(SQLTabCursor::new(self.ds.clone(), &self.cols))
+        Ok(SQLTabCursor::new(self.ds.clone(), &self.cols, &self.plans))
     }
 }
 
@@ -145,6 +258,8 @@ pub struct SQLTabCursor<'vtab> {
     reader: Option<Response>,
     /// 保存列的定义
     columns: &'vtab Columns,
+    /// 虚拟表的查询计划
+    plans: &'vtab Mutex<Vec<Plan>>,
     /// 记录下一行结果
     next: Option<Row>,
     /// 记录行号
@@ -155,7 +270,11 @@ pub struct SQLTabCursor<'vtab> {
 }
 
 impl SQLTabCursor<'_> {
-    fn new<'vtab>(ds: Arc<Box<dyn DataSource>>, cols: &'vtab Columns) -> SQLTabCursor<'vtab> {
+    fn new<'vtab>(
+        ds: Arc<Box<dyn DataSource>>,
+        cols: &'vtab Columns,
+        plans: &'vtab Mutex<Vec<Plan>>,
+    ) -> SQLTabCursor<'vtab> {
         SQLTabCursor {
             base: ffi::sqlite3_vtab_cursor::default(),
             reader: None,
@@ -163,14 +282,16 @@ impl SQLTabCursor<'_> {
             rowid: 0,
             ds,
             columns: cols,
+            plans,
             eof: false,
             phantom: PhantomData,
         }
     }
 
-    fn collect(&self, args: Args) -> Result<Response, Error> {
+    fn collect(&self, args: Args, filters: Filters) -> Result<Response, Error> {
         let data_source: Arc<Box<dyn DataSource>> = self.ds.clone();
         let (mut request, statement) = new_req_none(args);
+        request.set_filters(filters);
         // 执行请求
         let _ = std::thread::spawn(move || {
             if let Err(err) = data_source.collect(&mut request) {
@@ -185,18 +306,38 @@ impl SQLTabCursor<'_> {
 }
 
 unsafe impl VTabCursor for SQLTabCursor<'_> {
-    fn filter(&mut self, _idx_num: c_int, _idx_str: Option<&str>, args: &Values<'_>) -> Result<()> {
+    fn filter(&mut self, idx_num: c_int, _idx_str: Option<&str>, args: &Values<'_>) -> Result<()> {
+        let plan = self
+            .plans
+            .lock()
+            .get(idx_num as usize)
+            .cloned()
+            .unwrap_or_default();
         // 转换参数列表
-        let values: Vec<rusqlite::types::FromSqlResult<Value>> =
-            args.iter().map(|val| Value::column_result(val)).collect();
+        let mut values: Vec<Value> = vec![];
+        for val in args.iter() {
+            values.push(Value::column_result(val)?);
+        }
+        let mut values = values.into_iter();
+
+        // 未指定的参数使用空值填充
+        let len = plan.args.iter().max().map(|max| max + 1).unwrap_or(0);
+        let mut params = vec![Value::Nil; len];
+        for index in plan.args.iter() {
+            params[*index] = values.next().unwrap_or(Value::Nil);
+        }
         let mut args = Args::new();
-        for value in values {
-            let val = value?;
-            args.push(val);
+        for param in params {
+            args.push(param);
+        }
+
+        let mut filters = Filters::new();
+        for ((column, op), value) in plan.filters.iter().zip(values) {
+            filters.push(Filter::new(self.columns[*column].0.clone(), *op, value));
         }
-        debug!("args - {:?}", args);
+        debug!("args - {:?}, filters - {:?}", args, filters);
         // 执行请求
-        self.reader = Some(self.collect(args)?);
+        self.reader = Some(self.collect(args, filters)?);
         self.rowid = 0;
         // 先获取一次结果行
         self.next()?;
@@ -246,3 +387,125 @@ unsafe impl VTabCursor for SQLTabCursor<'_> {
         Ok(self.rowid as i64)
     }
 }
+
+#[test]
+fn test_filter_pushdown() {
+    let conn = crate::new_connection("sqlite:debug:default").unwrap();
+    let lines = |sql: &str| -> Vec<String> {
+        let statement = conn
+            .new_statement(sql, std::time::Duration::from_secs(2))
+            .unwrap();
+        statement
+            .wait()
+            .unwrap()
+            .map(|row| row.unwrap().get::<String>(0).unwrap())
+            .collect()
+    };
+
+    assert_eq!(
+        vec!["c", "d"],
+        lines("SELECT line FROM shell('a\nb\nc\nd', 10) WHERE stream = 'stdout' AND line_num > 1")
+    );
+    assert_eq!(
+        vec!["a", "d"],
+        lines("SELECT line FROM shell('a\nb\nc\nd', 10) WHERE stream = 'stdout' AND line_num IN (0, 3)")
+    );
+    assert_eq!(
+        vec!["b"],
+        lines(
+            "SELECT line FROM shell WHERE output = 'a\nb' AND _timeout = 10 AND stream LIKE 'std%' AND line_num >= 1"
+        )
+    );
+}
+
+#[cfg(test)]
+mod numbers {
+    use crate::{Columns, Promise, Result, Row, ToData, ToType};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// 数据源被执行的次数以及最后一次执行时下推的过滤条件数量
+    pub static INVOKED: AtomicUsize = AtomicUsize::new(0);
+    pub static FILTERS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Data)]
+    pub struct Number {
+        n: i64,
+    }
+
+    #[datasource(filter(n))]
+    pub fn numbers(count: i64, promise: &mut Promise<Number>) -> Result<()> {
+        INVOKED.fetch_add(1, Ordering::SeqCst);
+        FILTERS.store(promise.get_filters().iter().count(), Ordering::SeqCst);
+        for n in 0..count {
+            promise.commit(Number { n })?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_filter_plan() {
+    use crate::{Configure, Connection};
+    use std::sync::atomic::Ordering;
+
+    let conn = crate::connect::sqlite::SqliteSession::new().unwrap();
+    conn.register_source(crate::register_ds!(numbers)).unwrap();
+    let query = |sql: &str| -> (Vec<i64>, usize, usize) {
+        numbers::INVOKED.store(0, Ordering::SeqCst);
+        let statement = conn
+            .new_statement(sql, std::time::Duration::from_secs(2))
+            .unwrap();
+        let values = statement
+            .wait()
+            .unwrap()
+            .map(|row| row.unwrap().get::<i64>(0).unwrap())
+            .collect();
+        (
+            values,
+            numbers::INVOKED.load(Ordering::SeqCst),
+            numbers::FILTERS.load(Ordering::SeqCst),
+        )
+    };
+
+    assert_eq!(
+        (vec![2], 1, 1),
+        query("SELECT n FROM numbers(5) WHERE n = 2")
+    );
+    assert_eq!(
+        (vec![3, 4], 1, 1),
+        query("SELECT n FROM numbers(5) WHERE n > 2")
+    );
+    // `IN` 不下推，数据源只执行一次
+    assert_eq!(
+        (vec![0, 3], 1, 0),
+        query("SELECT n FROM numbers(5) WHERE n IN (0, 3)")
+    );
+    assert_eq!(
+        (vec![3], 1, 1),
+        query("SELECT n FROM numbers(5) WHERE n IN (0, 3) AND n > 1")
+    );
+}
+
+#[test]
+fn test_filter_collation() {
+    let conn = crate::new_connection("sqlite:debug:default").unwrap();
+    let count = |sql: &str| -> usize {
+        let statement = conn
+            .new_statement(sql, std::time::Duration::from_secs(2))
+            .unwrap();
+        statement.wait().unwrap().count()
+    };
+
+    assert_eq!(
+        2,
+        count("SELECT line FROM shell('a\nb', 10) WHERE stream = 'STDOUT' COLLATE NOCASE")
+    );
+    assert_eq!(
+        0,
+        count("SELECT line FROM shell('a\nb', 10) WHERE stream = 'STDOUT'")
+    );
+    assert_eq!(
+        2,
+        count("SELECT line FROM shell('a\nb', 10) WHERE stream LIKE 'STD%'")
+    );
+}
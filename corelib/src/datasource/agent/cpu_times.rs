@@ -0,0 +1,176 @@
+use crate::{Columns, Error, Promise, Row, ToData, ToType};
+
+use async_std::prelude::*;
+use async_std::task::block_on;
+use heim::cpu::{time, times, CpuTime};
+use heim::units::{time as unit, Time};
+use std::time::Duration;
+
+/// 汇总行的 CPU 名称
+const CPU_TOTAL: &str = "cpu-total";
+
+#[derive(Data)]
+pub struct CPUTimes {
+    pub cpu: String,
+    pub user: f64,
+    pub nice: f64,
+    pub system: f64,
+    pub idle: f64,
+    pub iowait: f64,
+    pub irq: f64,
+    pub softirq: f64,
+    pub steal: f64,
+}
+
+/// CPU 各状态的累计时间，单位 s
+#[derive(Default)]
+struct Times {
+    user: f64,
+    nice: f64,
+    system: f64,
+    idle: f64,
+    iowait: f64,
+    irq: f64,
+    softirq: f64,
+    steal: f64,
+}
+
+#[datasource]
+pub fn cpu_times(interval_ms: Option<u32>, promise: &mut Promise<CPUTimes>) -> Result<(), Error> {
+    let interval_ms = interval_ms.filter(|val| *val > 0).unwrap_or(1000);
+
+    let first = sample()?;
+    std::thread::sleep(Duration::from_millis(interval_ms as u64));
+    let last = sample()?;
+
+    for ((cpu, before), (_, after)) in first.iter().zip(last.iter()) {
+        promise.commit(percent(cpu, before, after))?;
+    }
+    Ok(())
+}
+
+/// 采样一次 CPU 时间，第一个元素为汇总值，其余为每个逻辑核心的值
+fn sample() -> Result<Vec<(String, Times)>, Error> {
+    block_on(async {
+        let mut rs = vec![(CPU_TOTAL.to_owned(), to_times(&time().await?))];
+        let mut cpus = Box::pin(times());
+        let mut index = 0;
+        while let Some(cpu) = cpus.next().await {
+            rs.push((format!("cpu{}", index), to_times(&cpu?)));
+            index += 1;
+        }
+        Ok(rs)
+    })
+}
+
+fn percent(cpu: &str, before: &Times, after: &Times) -> CPUTimes {
+    let total = |times: &Times| {
+        times.user
+            + times.nice
+            + times.system
+            + times.idle
+            + times.iowait
+            + times.irq
+            + times.softirq
+            + times.steal
+    };
+    let elapsed = total(after) - total(before);
+    let ratio = |now: f64, prev: f64| {
+        if elapsed > 0.0 {
+            ((now - prev).max(0.0) * 100.0 / elapsed).min(100.0)
+        } else {
+            0.0
+        }
+    };
+
+    CPUTimes {
+        cpu: cpu.to_owned(),
+        user: ratio(after.user, before.user),
+        nice: ratio(after.nice, before.nice),
+        system: ratio(after.system, before.system),
+        idle: ratio(after.idle, before.idle),
+        iowait: ratio(after.iowait, before.iowait),
+        irq: ratio(after.irq, before.irq),
+        softirq: ratio(after.softirq, before.softirq),
+        steal: ratio(after.steal, before.steal),
+    }
+}
+
+fn seconds(t: Time) -> f64 {
+    t.get::<unit::second>()
+}
+
+#[cfg(target_os = "linux")]
+fn to_times(cpu: &CpuTime) -> Times {
+    use heim::cpu::os::linux::CpuTimeExt;
+    Times {
+        user: seconds(cpu.user()),
+        nice: seconds(cpu.nice()),
+        system: seconds(cpu.system()),
+        idle: seconds(cpu.idle()),
+        iowait: seconds(cpu.io_wait()),
+        irq: seconds(cpu.irq()),
+        softirq: seconds(cpu.soft_irq()),
+        steal: seconds(cpu.steal()),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn to_times(cpu: &CpuTime) -> Times {
+    Times {
+        user: seconds(cpu.user()),
+        system: seconds(cpu.system()),
+        idle: seconds(cpu.idle()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_percent() {
+    let before = Times::default();
+    let after = Times {
+        user: 2.0,
+        system: 1.0,
+        idle: 6.0,
+        iowait: 1.0,
+        ..Default::default()
+    };
+    let rs = percent(CPU_TOTAL, &before, &after);
+    assert_eq!(20.0, rs.user);
+    assert_eq!(10.0, rs.system);
+    assert_eq!(60.0, rs.idle);
+    assert_eq!(10.0, rs.iowait);
+    assert_eq!(0.0, percent(CPU_TOTAL, &after, &after).idle);
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    let (req, resp) = crate::new_req(crate::args!(500), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<CPUTimes>().unwrap();
+        if let Err(err) = cpu_times(Some(500), &mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+        drop(req);
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "cpu", Number: "user", Number: "nice", Number: "system", Number: "idle", Number: "iowait", Number: "irq", Number: "softirq", Number: "steal"],
+        resp.columns()
+    );
+
+    let mut index = 0;
+    for row in resp {
+        let row = row.unwrap();
+        let cpu: String = row.get(0).unwrap();
+        if index == 0 {
+            assert_eq!(CPU_TOTAL, cpu);
+        }
+        index += 1;
+    }
+    assert_eq!(num_cpus::get() + 1, index);
+}
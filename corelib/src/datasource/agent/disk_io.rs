@@ -0,0 +1,181 @@
+use crate::{Columns, Error, Promise, Row, ToData, ToType};
+
+use async_std::prelude::*;
+use async_std::task::block_on;
+use heim::disk::io_counters;
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[derive(Data)]
+pub struct DiskIO {
+    pub device: String,
+    pub read_count: i64,
+    pub write_count: i64,
+    pub read_bytes: i64,
+    pub write_bytes: i64,
+    pub busy_time: i64,
+    pub in_flight: i64,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    pub util: f64,
+}
+
+/// 磁盘的累计计数
+#[derive(Clone)]
+struct Counter {
+    device: String,
+    read_count: u64,
+    write_count: u64,
+    read_bytes: u64,
+    write_bytes: u64,
+    busy_time: u64,
+    in_flight: u64,
+}
+
+#[datasource]
+pub fn disk_io(interval_ms: Option<u32>, promise: &mut Promise<DiskIO>) -> Result<(), Error> {
+    let first = counters()?;
+    let interval_ms = match interval_ms {
+        Some(interval_ms) if interval_ms > 0 => interval_ms,
+        // 未指定采样间隔时只返回累计值
+        _ => {
+            for counter in first {
+                promise.commit(to_row(&counter, None, 0))?;
+            }
+            return Ok(());
+        }
+    };
+
+    std::thread::sleep(Duration::from_millis(interval_ms as u64));
+    let last: HashMap<String, Counter> = first
+        .into_iter()
+        .map(|counter| (counter.device.clone(), counter))
+        .collect();
+    for counter in counters()? {
+        let prev = last.get(&counter.device);
+        promise.commit(to_row(&counter, prev, interval_ms))?;
+    }
+    Ok(())
+}
+
+fn to_row(counter: &Counter, prev: Option<&Counter>, interval_ms: u32) -> DiskIO {
+    let secs = interval_ms as f64 / 1000.0;
+    let (read_iops, write_iops, read_bytes_per_sec, write_bytes_per_sec, util) = match prev {
+        Some(prev) if interval_ms > 0 => {
+            let delta = |now: u64, before: u64| now.saturating_sub(before) as f64;
+            let busy = delta(counter.busy_time, prev.busy_time);
+            (
+                delta(counter.read_count, prev.read_count) / secs,
+                delta(counter.write_count, prev.write_count) / secs,
+                delta(counter.read_bytes, prev.read_bytes) / secs,
+                delta(counter.write_bytes, prev.write_bytes) / secs,
+                (busy * 100.0 / interval_ms as f64).min(100.0),
+            )
+        }
+        _ => (0.0, 0.0, 0.0, 0.0, 0.0),
+    };
+
+    DiskIO {
+        device: counter.device.clone(),
+        read_count: counter.read_count as i64,
+        write_count: counter.write_count as i64,
+        read_bytes: counter.read_bytes as i64,
+        write_bytes: counter.write_bytes as i64,
+        busy_time: counter.busy_time as i64,
+        in_flight: counter.in_flight as i64,
+        read_iops,
+        write_iops,
+        read_bytes_per_sec,
+        write_bytes_per_sec,
+        util,
+    }
+}
+
+fn counters() -> Result<Vec<Counter>, Error> {
+    let stats = disk_stats();
+    block_on(async {
+        let mut rs = vec![];
+        let mut counters = Box::pin(io_counters());
+        while let Some(counter) = counters.next().await {
+            let counter = counter?;
+            let device = counter.device_name().to_string_lossy().to_string();
+            let (busy_time, in_flight) = stats.get(&device).cloned().unwrap_or((0, 0));
+            rs.push(Counter {
+                device,
+                read_count: counter.read_count(),
+                write_count: counter.write_count(),
+                read_bytes: counter.read_bytes(),
+                write_bytes: counter.write_bytes(),
+                busy_time,
+                in_flight,
+            });
+        }
+        Ok(rs)
+    })
+}
+
+/// 获取磁盘的繁忙时间(ms) 和正在处理的 IO 数量
+#[cfg(target_os = "linux")]
+fn disk_stats() -> HashMap<String, (u64, u64)> {
+    let content = std::fs::read_to_string("/proc/diskstats").unwrap_or_default();
+    parse_disk_stats(&content)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn disk_stats() -> HashMap<String, (u64, u64)> {
+    HashMap::new()
+}
+
+/// 解析 `/proc/diskstats`，第 12 列为正在处理的 IO 数量，第 13 列为繁忙时间
+#[cfg(target_os = "linux")]
+fn parse_disk_stats(content: &str) -> HashMap<String, (u64, u64)> {
+    let mut stats = HashMap::new();
+    for line in content.lines() {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        if cols.len() < 13 {
+            continue;
+        }
+        let in_flight = cols[11].parse::<u64>().unwrap_or(0);
+        let busy_time = cols[12].parse::<u64>().unwrap_or(0);
+        stats.insert(cols[2].to_string(), (busy_time, in_flight));
+    }
+    stats
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_parse_disk_stats() {
+    let stats = parse_disk_stats(
+        "   8       0 sda 9841 2391 742486 4297 6617 8218 302832 11652 2 9532 15949 0 0 0 0\n",
+    );
+    assert_eq!(Some(&(9532, 2)), stats.get("sda"));
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    let (req, resp) = crate::new_req(crate::args!(500), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<DiskIO>().unwrap();
+        if let Err(err) = disk_io(Some(500), &mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+        drop(req);
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "device", Integer: "read_count", Integer: "write_count", Integer: "read_bytes", Integer: "write_bytes", Integer: "busy_time", Integer: "in_flight", Number: "read_iops", Number: "write_iops", Number: "read_bytes_per_sec", Number: "write_bytes_per_sec", Number: "util"],
+        resp.columns()
+    );
+
+    for row in resp {
+        let row = row.unwrap();
+        let util: f64 = row.get(11).unwrap();
+        assert!(util >= 0.0 && util <= 100.0);
+    }
+}
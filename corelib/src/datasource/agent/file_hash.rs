@@ -0,0 +1,49 @@
+use crate::datasource::{hash_reader, FileHash};
+use crate::{Promise, Result, ToData, ToType};
+use std::fs::File;
+
+#[datasource]
+pub fn file_hash(path: String, algorithm: String, promise: &mut Promise<FileHash>) -> Result<()> {
+    let file = File::open(&path)?;
+    let (hash, size) = hash_reader(file, &algorithm)?;
+    promise.commit(FileHash {
+        path,
+        algorithm: algorithm.to_lowercase(),
+        hash,
+        size: size as i64,
+    })?;
+    Ok(())
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    const PATH: &str = "/tmp/test_file_hash.log";
+    std::fs::write(&PATH, "Hello world").unwrap();
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<FileHash>().unwrap();
+        if let Err(err) = file_hash(PATH.to_string(), "md5".to_owned(), &mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "path", String: "algorithm", String: "hash", Integer: "size"],
+        resp.columns()
+    );
+
+    let mut index = 0;
+    for row in resp {
+        let row = row.unwrap();
+        let hash: String = row.get(2).unwrap();
+        let size: i64 = row.get(3).unwrap();
+        assert_eq!("3e25960a79dbc69b674cd4ec67a72c62", hash);
+        assert_eq!(11, size);
+        index += 1;
+    }
+    assert_eq!(1, index);
+}
@@ -1,28 +1,37 @@
-use crate::{Columns, Error, Promise, Row, ToData};
+#[cfg(target_os = "linux")]
+use crate::datasource::unescape;
+use crate::datasource::Filesystem;
+use crate::{Error, Promise, ToData, ToType};
 
 use async_std::prelude::*;
 use async_std::task::block_on;
-use heim::disk::partitions_physical;
+use heim::disk::{partitions, partitions_physical, Partition};
+use std::collections::HashMap;
 use std::ffi::OsStr;
+use std::path::Path;
 
-#[derive(Data)]
-pub struct Filesystem {
-    pub name: String,
-    pub mount_on: String,
-    pub total_bytes: i64,
-    pub used_bytes: i64,
-    pub free_bytes: i64,
+/// 文件系统的 inode 统计以及是否只读
+#[derive(Default)]
+struct Stat {
+    inodes_total: u64,
+    inodes_free: u64,
+    read_only: bool,
 }
 
 #[datasource]
-pub fn filesystem(promise: &mut Promise<Filesystem>) -> Result<(), Error> {
+pub fn filesystem(all: Option<bool>, promise: &mut Promise<Filesystem>) -> Result<(), Error> {
+    let options = mount_options();
     block_on(async {
-        let mut partitions = Box::pin(partitions_physical());
-        while let Some(Ok(part)) = partitions.next().await {
+        // 默认只返回物理磁盘分区，`all` 为 true 时包含 tmpfs、NFS 等挂载点
+        let parts = if all.unwrap_or(false) {
+            collect(partitions()).await
+        } else {
+            collect(partitions_physical()).await
+        };
+        for part in parts {
             if let Ok(usage) = heim::disk::usage(part.mount_point().to_path_buf()).await {
-                let total = usage.total();
-                if total == 0 {
-                    break;
+                if usage.total() == 0 {
+                    continue;
                 }
                 let filesystem: String = format!(
                     "{}",
@@ -32,19 +41,98 @@ pub fn filesystem(promise: &mut Promise<Filesystem>) -> Result<(), Error> {
                 );
 
                 let mount_on = format!("{}", part.mount_point().to_string_lossy());
-                if let Err(_) = promise.commit(Filesystem {
+                let stat = stat(part.mount_point());
+                let options = options.get(&mount_on).cloned().unwrap_or_default();
+                let read_only = stat.read_only || options.split(',').any(|opt| opt == "ro");
+                promise.commit(Filesystem {
                     name: filesystem,
                     used_bytes: usage.used() as i64,
                     total_bytes: usage.total() as i64,
                     free_bytes: usage.free() as i64,
                     mount_on,
-                }) {
-                    break;
-                }
+                    fs_type: part.file_system().as_str().to_owned(),
+                    options,
+                    inodes_total: stat.inodes_total as i64,
+                    inodes_used: stat.inodes_total.saturating_sub(stat.inodes_free) as i64,
+                    inodes_free: stat.inodes_free as i64,
+                    read_only,
+                })?;
             }
         }
-    });
-    Ok(())
+        Ok(())
+    })
+}
+
+async fn collect<E, S: Stream<Item = Result<Partition, E>>>(stream: S) -> Vec<Partition> {
+    let mut stream = Box::pin(stream);
+    let mut parts = vec![];
+    while let Some(part) = stream.next().await {
+        if let Ok(part) = part {
+            parts.push(part);
+        }
+    }
+    parts
+}
+
+#[cfg(unix)]
+fn stat(path: &Path) -> Stat {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = match CString::new(path.as_os_str().as_bytes()) {
+        Ok(path) => path,
+        Err(_) => return Stat::default(),
+    };
+    let mut vfs: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut vfs) } != 0 {
+        return Stat::default();
+    }
+    Stat {
+        inodes_total: vfs.f_files as u64,
+        inodes_free: vfs.f_ffree as u64,
+        read_only: vfs.f_flag & libc::ST_RDONLY == libc::ST_RDONLY,
+    }
+}
+
+#[cfg(not(unix))]
+fn stat(_: &Path) -> Stat {
+    Stat::default()
+}
+
+/// 获取挂载点的挂载参数
+#[cfg(target_os = "linux")]
+fn mount_options() -> HashMap<String, String> {
+    let content = std::fs::read_to_string("/proc/mounts").unwrap_or_default();
+    parse_mounts(&content)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn mount_options() -> HashMap<String, String> {
+    HashMap::new()
+}
+
+/// 解析 `/proc/mounts`，挂载点中的空格等字符会被转义为 `\040` 形式
+#[cfg(target_os = "linux")]
+fn parse_mounts(content: &str) -> HashMap<String, String> {
+    let mut mounts = HashMap::new();
+    for line in content.lines() {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        if cols.len() < 4 {
+            continue;
+        }
+        mounts.insert(unescape(cols[1]), cols[3].to_owned());
+    }
+    mounts
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_parse_mounts() {
+    let mounts = parse_mounts(
+        "/dev/sda1 / ext4 rw,relatime 0 0\n/dev/sdb1 /mnt/my\\040disk xfs ro,noatime 0 0\n",
+    );
+    assert_eq!(Some(&"rw,relatime".to_owned()), mounts.get("/"));
+    assert_eq!(Some(&"ro,noatime".to_owned()), mounts.get("/mnt/my disk"));
 }
 
 #[test]
@@ -53,7 +141,7 @@ fn test() {
     let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
     async_std::task::spawn_blocking(move || {
         let mut promise = req.head::<Filesystem>().unwrap();
-        if let Err(err) = filesystem(&mut promise) {
+        if let Err(err) = filesystem(None, &mut promise) {
             let _ = req.error(err);
         } else {
             let _ = req.ok();
@@ -63,7 +151,7 @@ fn test() {
 
     let resp = resp.wait().unwrap();
     assert_eq!(
-        &columns![String: "name", String: "mount_on", Integer: "total_bytes",Integer: "used_bytes", Integer: "free_bytes"],
+        &columns![String: "name", String: "mount_on", Integer: "total_bytes",Integer: "used_bytes", Integer: "free_bytes", String: "fs_type", String: "options", Integer: "inodes_total", Integer: "inodes_used", Integer: "inodes_free", Boolean: "read_only"],
         resp.columns()
     );
 
@@ -0,0 +1,134 @@
+use crate::datasource::{name_matcher, FoundFile};
+use crate::{Promise, Result, ToData, ToType};
+use regex::Regex;
+use std::fs::{read_dir, symlink_metadata};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// 查找文件时的过滤条件
+struct Filter {
+    matcher: Regex,
+    max_depth: u32,
+    exclude_hidden: bool,
+    max_files: u32,
+}
+
+/// 查找本地文件，每个目录中的文件按照文件名排序后深度优先遍历
+///
+/// 返回的顺序只是当前的实现方式，不做保证，远程模式的顺序可能不同
+#[datasource]
+pub fn find_files(
+    root: String,
+    pattern: String,
+    max_depth: Option<u32>,
+    exclude_hidden: Option<bool>,
+    max_files: Option<u32>,
+    promise: &mut Promise<FoundFile>,
+) -> Result<()> {
+    let filter = Filter {
+        matcher: name_matcher(&pattern)?,
+        max_depth: max_depth.filter(|depth| *depth > 0).unwrap_or(u32::MAX),
+        exclude_hidden: exclude_hidden.unwrap_or(false),
+        max_files: max_files.filter(|max| *max > 0).unwrap_or(u32::MAX),
+    };
+    info!("find files - {} in {}", pattern, root);
+    let mut count = 0;
+    walk(Path::new(&root), 1, &filter, &mut count, promise)
+}
+
+fn walk(
+    dir: &Path,
+    depth: u32,
+    filter: &Filter,
+    count: &mut u32,
+    promise: &mut Promise<FoundFile>,
+) -> Result<()> {
+    let entries = match read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if depth == 1 => return Err(err.into()),
+        // 无权限访问的子目录会被忽略
+        Err(_) => return Ok(()),
+    };
+    let mut entries: Vec<_> = entries.filter_map(|entry| entry.ok()).collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        if *count >= filter.max_files {
+            break;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if filter.exclude_hidden && name.starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let meta = match symlink_metadata(&path) {
+            Ok(meta) => meta,
+            Err(_) => continue,
+        };
+
+        if meta.is_dir() {
+            if depth < filter.max_depth {
+                walk(&path, depth + 1, filter, count, promise)?;
+            }
+        } else if meta.is_file() && filter.matcher.is_match(&name) {
+            let mtime = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|t| t.as_secs() as i64)
+                .unwrap_or(0);
+            promise.commit(FoundFile {
+                path: path.to_string_lossy().to_string(),
+                name,
+                size: meta.len() as i64,
+                mtime,
+            })?;
+            *count += 1;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    const PATH: &str = "/tmp/test_find_files";
+    let _ = std::fs::remove_dir_all(PATH);
+    std::fs::create_dir_all(format!("{}/trace/.hidden", PATH)).unwrap();
+    std::fs::write(format!("{}/alert_orcl.log", PATH), "Hello").unwrap();
+    std::fs::write(format!("{}/trace/alert_orcl2.log", PATH), "Hello").unwrap();
+    std::fs::write(format!("{}/trace/.hidden/alert_old.log", PATH), "Hello").unwrap();
+    std::fs::write(format!("{}/trace/orcl_ora_1.trc", PATH), "Hello").unwrap();
+
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<FoundFile>().unwrap();
+        if let Err(err) = find_files(
+            PATH.to_string(),
+            "alert_*.log".to_owned(),
+            None,
+            Some(true),
+            None,
+            &mut promise,
+        ) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "path", String: "name", Integer: "size", Integer: "mtime"],
+        resp.columns()
+    );
+
+    let mut names = vec![];
+    for row in resp {
+        let row = row.unwrap();
+        let name: String = row.get(1).unwrap();
+        names.push(name);
+    }
+    assert_eq!(vec!["alert_orcl.log", "alert_orcl2.log"], names);
+    std::fs::remove_dir_all(PATH).unwrap();
+}
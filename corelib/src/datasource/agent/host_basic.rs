@@ -1,4 +1,4 @@
-use crate::{Columns, Error, Promise, Row, ToData};
+use crate::{datasource::HostBasic, Error, Promise, ToData};
 
 use super::{format, run_command};
 use async_std::task::block_on;
@@ -31,15 +31,6 @@ fn cpu_brand() -> Result<String, Error> {
     return Ok(rs);
 }
 
-#[derive(Data)]
-pub struct HostBasic {
-    pub host_name: String,
-    pub cpu_core: i64,
-    pub cpu_model: String,
-    pub uptime: i64,
-    pub memory: i64,
-}
-
 #[datasource]
 pub fn host_basic(promise: &mut Promise<HostBasic>) -> Result<(), Error> {
     let platform: Platform = block_on(platform())?;
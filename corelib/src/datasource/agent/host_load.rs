@@ -0,0 +1,112 @@
+use crate::{Columns, Error, Promise, Row, ToData};
+
+use super::format;
+use async_std::task::block_on;
+use heim::host::uptime;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Data)]
+pub struct HostLoad {
+    pub load1: f64,
+    pub load5: f64,
+    pub load15: f64,
+    pub run_queue: i64,
+    pub uptime: i64,
+    pub boot_time: i64,
+}
+
+#[datasource]
+pub fn host_load(promise: &mut Promise<HostLoad>) -> Result<(), Error> {
+    let (load1, load5, load15) = load_avg()?;
+    let uptime = format(block_on(uptime())?) as i64;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs() as i64)
+        .unwrap_or(0);
+
+    promise.commit(HostLoad {
+        load1,
+        load5,
+        load15,
+        run_queue: run_queue(),
+        uptime,
+        boot_time: now - uptime,
+    })?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn load_avg() -> Result<(f64, f64, f64), Error> {
+    let mut loads = [0f64; 3];
+    if unsafe { libc::getloadavg(loads.as_mut_ptr(), 3) } != 3 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok((loads[0], loads[1], loads[2]))
+}
+
+#[cfg(not(unix))]
+fn load_avg() -> Result<(f64, f64, f64), Error> {
+    Err(super::unsupported("host_load"))
+}
+
+/// 获取正在运行的进程数量
+#[cfg(target_os = "linux")]
+fn run_queue() -> i64 {
+    std::fs::read_to_string("/proc/loadavg")
+        .map(|content| parse_run_queue(&content))
+        .unwrap_or(0)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn run_queue() -> i64 {
+    0
+}
+
+/// 解析 `/proc/loadavg`，第四列为 `正在运行的进程数/总进程数`
+#[cfg(target_os = "linux")]
+fn parse_run_queue(content: &str) -> i64 {
+    content
+        .split_whitespace()
+        .nth(3)
+        .and_then(|val| val.split('/').next())
+        .and_then(|val| val.parse::<i64>().ok())
+        .unwrap_or(0)
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_parse_run_queue() {
+    assert_eq!(3, parse_run_queue("0.52 0.58 0.59 3/467 12345\n"));
+    assert_eq!(0, parse_run_queue(""));
+}
+
+#[test]
+#[cfg(unix)]
+fn test() {
+    use crate::*;
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<HostLoad>().unwrap();
+        if let Err(err) = host_load(&mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+        drop(req);
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![Number: "load1", Number: "load5", Number: "load15", Integer: "run_queue", Integer: "uptime", Integer: "boot_time"],
+        resp.columns()
+    );
+
+    let mut index = 0;
+    for row in resp {
+        let row = row.unwrap();
+        let boot_time: i64 = row.get(5).unwrap();
+        assert!(boot_time > 0);
+        index += 1;
+    }
+    assert_eq!(1, index);
+}
@@ -1,14 +1,7 @@
-use crate::{Columns, Promise, Result, Row, ToData};
+use crate::{datasource::MemoryUsage, Promise, Result, ToData};
 use async_std::task::block_on;
 use heim::memory::{memory, Memory};
 
-#[derive(Data)]
-pub struct MemoryUsage {
-    used_bytes: i64,
-    total_bytes: i64,
-    free_bytes: i64,
-}
-
 #[datasource]
 pub fn memory_usage(promise: &mut Promise<MemoryUsage>) -> Result<()> {
     let memory: Memory = block_on(memory())?;
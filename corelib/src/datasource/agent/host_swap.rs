@@ -1,14 +1,7 @@
-use crate::{Columns, Promise, Result, Row, ToData};
+use crate::{datasource::SWAPUsage, Promise, Result, ToData};
 use async_std::task::block_on;
 use heim::memory::{swap, Swap};
 
-#[derive(Data)]
-pub struct SWAPUsage {
-    used_bytes: i64,
-    total_bytes: i64,
-    free_bytes: i64,
-}
-
 #[datasource]
 pub fn swap_usage(promise: &mut Promise<SWAPUsage>) -> Result<()> {
     let swap: Swap = block_on(swap())?;
@@ -0,0 +1,106 @@
+use crate::{Columns, Error, Promise, Row, ToData};
+
+#[derive(Data)]
+pub struct HostUser {
+    pub user: String,
+    pub terminal: String,
+    pub host: String,
+    pub login_time: i64,
+}
+
+#[datasource]
+pub fn host_users(promise: &mut Promise<HostUser>) -> Result<(), Error> {
+    for user in users()? {
+        promise.commit(user)?;
+    }
+    Ok(())
+}
+
+/// 通过 utmpx 获取当前登录的用户会话
+#[cfg(unix)]
+fn users() -> Result<Vec<HostUser>, Error> {
+    use parking_lot::{const_mutex, Mutex};
+
+    // getutxent 不是线程安全的，需要串行访问
+    static UTMPX: Mutex<()> = const_mutex(());
+    let _lock = UTMPX.lock();
+
+    let mut users = vec![];
+    unsafe {
+        libc::setutxent();
+        loop {
+            let entry = libc::getutxent();
+            if entry.is_null() {
+                break;
+            }
+            let entry = &*entry;
+            if entry.ut_type != libc::USER_PROCESS {
+                continue;
+            }
+            users.push(HostUser {
+                user: to_string(&entry.ut_user),
+                terminal: to_string(&entry.ut_line),
+                host: to_string(&entry.ut_host),
+                login_time: entry.ut_tv.tv_sec as i64,
+            });
+        }
+        libc::endutxent();
+    }
+    Ok(users)
+}
+
+#[cfg(not(unix))]
+fn users() -> Result<Vec<HostUser>, Error> {
+    Err(super::unsupported("host_users"))
+}
+
+/// utmpx 中的字段为定长数组，不一定以 `\0` 结尾
+#[cfg(unix)]
+fn to_string(value: &[libc::c_char]) -> String {
+    let bytes: Vec<u8> = value
+        .iter()
+        .take_while(|ch| **ch != 0)
+        .map(|ch| *ch as u8)
+        .collect();
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+#[test]
+#[cfg(unix)]
+fn test_to_string() {
+    let value = [
+        b'r' as libc::c_char,
+        b'o' as libc::c_char,
+        0,
+        b'x' as libc::c_char,
+    ];
+    assert_eq!("ro", to_string(&value));
+    let value = [b'p' as libc::c_char, b't' as libc::c_char];
+    assert_eq!("pt", to_string(&value));
+}
+
+#[test]
+#[cfg(unix)]
+fn test() {
+    use crate::*;
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<HostUser>().unwrap();
+        if let Err(err) = host_users(&mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+        drop(req);
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "user", String: "terminal", String: "host", Integer: "login_time"],
+        resp.columns()
+    );
+
+    for row in resp {
+        let _ = row.unwrap();
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{datasource::FileEntry, Promise, Result, ToData, ToType};
+use std::fs::{read_dir, symlink_metadata, FileType, Metadata};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[datasource]
+pub fn list_dir(
+    path: String,
+    recursive: Option<bool>,
+    max_depth: Option<u32>,
+    promise: &mut Promise<FileEntry>,
+) -> Result<()> {
+    // 非递归时只列出当前目录，递归时未指定深度则不限制
+    let max_depth = if recursive.unwrap_or(false) {
+        max_depth.filter(|depth| *depth > 0).unwrap_or(u32::MAX)
+    } else {
+        1
+    };
+    info!("list dir - {} with max depth = {}", path, max_depth);
+    walk(Path::new(&path), 1, max_depth, promise)
+}
+
+fn walk(dir: &Path, depth: u32, max_depth: u32, promise: &mut Promise<FileEntry>) -> Result<()> {
+    let entries = match read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if depth == 1 => return Err(err.into()),
+        // 无权限访问的子目录会被忽略
+        Err(_) => return Ok(()),
+    };
+    let mut entries: Vec<_> = entries.filter_map(|entry| entry.ok()).collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        // 文件可能在遍历过程中被删除
+        let meta = match symlink_metadata(&path) {
+            Ok(meta) => meta,
+            Err(_) => continue,
+        };
+        promise.commit(to_entry(&path, &meta))?;
+
+        // 不跟随符号链接，避免循环遍历
+        if meta.is_dir() && depth < max_depth {
+            walk(&path, depth + 1, max_depth, promise)?;
+        }
+    }
+    Ok(())
+}
+
+fn to_entry(path: &Path, meta: &Metadata) -> FileEntry {
+    let link_target = if meta.file_type().is_symlink() {
+        std::fs::read_link(path)
+            .map(|target| target.to_string_lossy().to_string())
+            .unwrap_or_default()
+    } else {
+        String::new()
+    };
+    let (mode, owner, group, atime, ctime) = unix_meta(meta);
+
+    FileEntry {
+        name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        path: path.to_string_lossy().to_string(),
+        file_type: file_type(&meta.file_type()).to_owned(),
+        size: meta.len() as i64,
+        mode,
+        owner,
+        group,
+        mtime: timestamp(meta.modified()),
+        atime,
+        ctime,
+        link_target,
+    }
+}
+
+#[cfg(unix)]
+fn file_type(t: &FileType) -> &'static str {
+    use std::os::unix::fs::FileTypeExt;
+    if t.is_symlink() {
+        "symlink"
+    } else if t.is_dir() {
+        "dir"
+    } else if t.is_file() {
+        "file"
+    } else if t.is_block_device() {
+        "block"
+    } else if t.is_char_device() {
+        "char"
+    } else if t.is_fifo() {
+        "fifo"
+    } else if t.is_socket() {
+        "socket"
+    } else {
+        "other"
+    }
+}
+
+#[cfg(not(unix))]
+fn file_type(t: &FileType) -> &'static str {
+    if t.is_symlink() {
+        "symlink"
+    } else if t.is_dir() {
+        "dir"
+    } else if t.is_file() {
+        "file"
+    } else {
+        "other"
+    }
+}
+
+/// 获取权限位、属主、属组以及访问时间和状态变更时间
+#[cfg(unix)]
+fn unix_meta(meta: &Metadata) -> (String, String, String, i64, i64) {
+    use std::os::unix::fs::MetadataExt;
+    (
+        format!("{:o}", meta.mode() & 0o7777),
+        super::user_name(meta.uid()),
+        super::group_name(meta.gid()),
+        meta.atime(),
+        meta.ctime(),
+    )
+}
+
+#[cfg(not(unix))]
+fn unix_meta(meta: &Metadata) -> (String, String, String, i64, i64) {
+    (
+        String::new(),
+        String::new(),
+        String::new(),
+        timestamp(meta.accessed()),
+        timestamp(meta.created()),
+    )
+}
+
+fn timestamp(t: std::io::Result<SystemTime>) -> i64 {
+    t.ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|t| t.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    const PATH: &str = "/tmp/test_list_dir";
+    let _ = std::fs::remove_dir_all(PATH);
+    std::fs::create_dir_all(format!("{}/sub", PATH)).unwrap();
+    std::fs::write(format!("{}/a.log", PATH), "Hello").unwrap();
+    std::fs::write(format!("{}/sub/b.log", PATH), "Hello world").unwrap();
+
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<FileEntry>().unwrap();
+        if let Err(err) = list_dir(PATH.to_string(), Some(true), None, &mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "name", String: "path", String: "file_type", Integer: "size", String: "mode", String: "owner", String: "group", Integer: "mtime", Integer: "atime", Integer: "ctime", String: "link_target"],
+        resp.columns()
+    );
+
+    let mut names = vec![];
+    for row in resp {
+        let row = row.unwrap();
+        let name: String = row.get(0).unwrap();
+        let file_type: String = row.get(2).unwrap();
+        let size: i64 = row.get(3).unwrap();
+        if name == "b.log" {
+            assert_eq!("file", file_type);
+            assert_eq!(11, size);
+        }
+        names.push(name);
+    }
+    assert_eq!(vec!["a.log", "sub", "b.log"], names);
+    std::fs::remove_dir_all(PATH).unwrap();
+}
@@ -4,17 +4,42 @@ use std::process::{Command, Output};
 use std::sync::Arc;
 use std::time::Duration;
 
+mod cpu_times;
 mod cpu_usage;
+mod disk_io;
+mod file_hash;
 mod filesystem;
+mod find_files;
 mod host_basic;
+mod host_load;
 mod host_mem;
 mod host_swap;
+mod host_users;
+mod list_dir;
 mod mkdir;
+mod net_connections;
+mod net_io;
 mod os_info;
+mod process;
 mod read_file;
 mod shell;
+mod tail_file;
 mod write_file;
 
+#[cfg(not(target_os = "linux"))]
+const BASE_CODE: i32 = 65 + 71 + 84;
+#[cfg(not(target_os = "linux"))]
+const UNSUPPORTED_PLATFORM: i32 = code!(BASE_CODE, 100);
+
+/// 当前平台不支持该数据源
+#[cfg(not(target_os = "linux"))]
+fn unsupported(name: &str) -> Error {
+    Error::other(
+        UNSUPPORTED_PLATFORM,
+        format!("{} is not supported on {}", name, std::env::consts::OS),
+    )
+}
+
 impl From<heim::Error> for Error {
     fn from(err: heim::Error) -> Self {
         return Error::internal(0x00, format!("{}", err));
@@ -50,6 +75,54 @@ fn run_command(cmd: &str) -> Result<String> {
     Ok(line)
 }
 
+/// 通过 uid 获取用户名，获取失败时返回 uid 本身
+#[cfg(unix)]
+fn user_name(uid: u32) -> String {
+    use std::ffi::CStr;
+    let mut buf = vec![0 as libc::c_char; 1024];
+    let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    let rs = unsafe {
+        libc::getpwuid_r(
+            uid as libc::uid_t,
+            &mut pwd,
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut result,
+        )
+    };
+    if rs != 0 || result.is_null() {
+        return uid.to_string();
+    }
+    unsafe { CStr::from_ptr(pwd.pw_name) }
+        .to_string_lossy()
+        .to_string()
+}
+
+/// 通过 gid 获取用户组名，获取失败时返回 gid 本身
+#[cfg(unix)]
+fn group_name(gid: u32) -> String {
+    use std::ffi::CStr;
+    let mut buf = vec![0 as libc::c_char; 1024];
+    let mut grp: libc::group = unsafe { std::mem::zeroed() };
+    let mut result: *mut libc::group = std::ptr::null_mut();
+    let rs = unsafe {
+        libc::getgrgid_r(
+            gid as libc::gid_t,
+            &mut grp,
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut result,
+        )
+    };
+    if rs != 0 || result.is_null() {
+        return gid.to_string();
+    }
+    unsafe { CStr::from_ptr(grp.gr_name) }
+        .to_string_lossy()
+        .to_string()
+}
+
 /// 注册数据源
 pub fn register_ds<T: Configure>(instance: &Instance, connection: &T) -> Result<()> {
     use crate::register_ds;
@@ -65,6 +138,17 @@ pub fn register_ds<T: Configure>(instance: &Instance, connection: &T) -> Result<
     connection.register_source(register_ds!(os_info: instance))?;
     connection.register_source(register_ds!(host_mem: instance))?;
     connection.register_source(register_ds!(host_swap: instance))?;
+    connection.register_source(register_ds!(process: instance))?;
+    connection.register_source(register_ds!(net_io: instance))?;
+    connection.register_source(register_ds!(net_connections: instance))?;
+    connection.register_source(register_ds!(disk_io: instance))?;
+    connection.register_source(register_ds!(cpu_times: instance))?;
+    connection.register_source(register_ds!(host_load: instance))?;
+    connection.register_source(register_ds!(host_users: instance))?;
+    connection.register_source(register_ds!(list_dir: instance))?;
+    connection.register_source(register_ds!(tail_file: instance))?;
+    connection.register_source(register_ds!(find_files: instance))?;
+    connection.register_source(register_ds!(file_hash: instance))?;
     Ok(())
 }
 
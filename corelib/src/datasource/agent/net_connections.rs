@@ -0,0 +1,199 @@
+#[cfg(target_os = "linux")]
+use crate::Error;
+use crate::{Columns, Promise, Result, Row, ToData};
+
+#[derive(Data)]
+pub struct NetConnection {
+    pub protocol: String,
+    pub local_addr: String,
+    pub local_port: i64,
+    pub remote_addr: String,
+    pub remote_port: i64,
+    pub state: String,
+    pub pid: i64,
+}
+
+#[datasource]
+pub fn net_connections(promise: &mut Promise<NetConnection>) -> Result<()> {
+    connections(promise)
+}
+
+#[cfg(target_os = "linux")]
+fn connections(promise: &mut Promise<NetConnection>) -> Result<()> {
+    let inodes = socket_inodes();
+    for protocol in &["tcp", "tcp6", "udp", "udp6"] {
+        let content = match std::fs::read_to_string(format!("/proc/net/{}", protocol)) {
+            Ok(content) => content,
+            // 未开启 IPv6 时不存在对应的文件
+            Err(_) => continue,
+        };
+
+        // 第一行为标题行
+        for line in content.lines().skip(1) {
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            if cols.len() < 10 {
+                continue;
+            }
+            let (local_addr, local_port) = parse_addr(cols[1])?;
+            let (remote_addr, remote_port) = parse_addr(cols[2])?;
+            let state = if protocol.starts_with("tcp") {
+                tcp_state(cols[3])
+            } else {
+                "NONE"
+            };
+            let pid = cols[9]
+                .parse::<u64>()
+                .ok()
+                .and_then(|inode| inodes.get(&inode))
+                .copied()
+                .unwrap_or(0);
+
+            promise.commit(NetConnection {
+                protocol: protocol.to_string(),
+                local_addr,
+                local_port,
+                remote_addr,
+                remote_port,
+                state: state.to_owned(),
+                pid,
+            })?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn connections(_: &mut Promise<NetConnection>) -> Result<()> {
+    Err(super::unsupported("net_connections"))
+}
+
+/// 解析 `/proc/net/*` 中的地址，格式为 `0100007F:0016`
+#[cfg(target_os = "linux")]
+fn parse_addr(value: &str) -> Result<(String, i64)> {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    let mut items = value.split(':');
+    let addr = items.next().unwrap_or("");
+    let port = i64::from_str_radix(items.next().unwrap_or("0"), 16)?;
+
+    // 地址按照 32 位的主机字节序输出
+    let mut bytes = vec![];
+    for index in (0..addr.len()).step_by(8) {
+        let word = addr
+            .get(index..index + 8)
+            .ok_or_else(|| Error::invalid_type(format!("invalid address - {}", value)))?;
+        bytes.extend_from_slice(&u32::from_str_radix(word, 16)?.to_ne_bytes());
+    }
+
+    let addr = match bytes.len() {
+        4 => Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]).to_string(),
+        16 => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(&bytes);
+            Ipv6Addr::from(octets).to_string()
+        }
+        _ => return Err(Error::invalid_type(format!("invalid address - {}", value))),
+    };
+    Ok((addr, port))
+}
+
+#[cfg(target_os = "linux")]
+fn tcp_state(value: &str) -> &'static str {
+    match value {
+        "01" => "ESTABLISHED",
+        "02" => "SYN_SENT",
+        "03" => "SYN_RECV",
+        "04" => "FIN_WAIT1",
+        "05" => "FIN_WAIT2",
+        "06" => "TIME_WAIT",
+        "07" => "CLOSE",
+        "08" => "CLOSE_WAIT",
+        "09" => "LAST_ACK",
+        "0A" => "LISTEN",
+        "0B" => "CLOSING",
+        _ => "UNKNOWN",
+    }
+}
+
+/// 获取 socket inode 与进程号的对应关系，无权限访问的进程会被忽略
+#[cfg(target_os = "linux")]
+fn socket_inodes() -> std::collections::HashMap<u64, i64> {
+    let mut inodes = std::collections::HashMap::new();
+    let procs = match std::fs::read_dir("/proc") {
+        Ok(procs) => procs,
+        Err(_) => return inodes,
+    };
+    for entry in procs.filter_map(|entry| entry.ok()) {
+        let pid = match entry.file_name().to_string_lossy().parse::<i64>() {
+            Ok(pid) => pid,
+            Err(_) => continue,
+        };
+        let fds = match std::fs::read_dir(entry.path().join("fd")) {
+            Ok(fds) => fds,
+            Err(_) => continue,
+        };
+        for fd in fds.filter_map(|fd| fd.ok()) {
+            if let Ok(link) = std::fs::read_link(fd.path()) {
+                let link = link.to_string_lossy();
+                if link.starts_with("socket:[") {
+                    if let Ok(inode) = link[8..link.len() - 1].parse::<u64>() {
+                        inodes.insert(inode, pid);
+                    }
+                }
+            }
+        }
+    }
+    inodes
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_parse_addr() {
+    assert_eq!(
+        ("127.0.0.1".to_owned(), 22),
+        parse_addr("0100007F:0016").unwrap()
+    );
+    assert_eq!(
+        ("::1".to_owned(), 8080),
+        parse_addr("00000000000000000000000001000000:1F90").unwrap()
+    );
+    assert!(parse_addr("0100007:0016").is_err());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test() {
+    use crate::*;
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port() as i64;
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<NetConnection>().unwrap();
+        if let Err(err) = net_connections(&mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+        drop(req);
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "protocol", String: "local_addr", Integer: "local_port", String: "remote_addr", Integer: "remote_port", String: "state", Integer: "pid"],
+        resp.columns()
+    );
+
+    let mut has_listener = false;
+    for row in resp {
+        let row = row.unwrap();
+        let local_port: i64 = row.get(2).unwrap();
+        let state: String = row.get(5).unwrap();
+        let pid: i64 = row.get(6).unwrap();
+        if local_port == port && state == "LISTEN" {
+            assert_eq!(std::process::id() as i64, pid);
+            has_listener = true;
+        }
+    }
+    assert!(has_listener);
+    drop(listener);
+}
@@ -0,0 +1,79 @@
+use crate::{Columns, Error, Promise, Row, ToData};
+
+use async_std::prelude::*;
+use async_std::task::block_on;
+use heim::net::{io_counters, IoCounters};
+
+#[derive(Data)]
+pub struct NetIO {
+    pub interface: String,
+    pub bytes_sent: i64,
+    pub bytes_recv: i64,
+    pub packets_sent: i64,
+    pub packets_recv: i64,
+    pub errors_sent: i64,
+    pub errors_recv: i64,
+    pub drop_sent: i64,
+    pub drop_recv: i64,
+}
+
+#[datasource]
+pub fn net_io(promise: &mut Promise<NetIO>) -> Result<(), Error> {
+    block_on(async {
+        let mut counters = Box::pin(io_counters());
+        while let Some(counter) = counters.next().await {
+            let counter: IoCounters = counter?;
+            promise.commit(NetIO {
+                interface: counter.interface().to_string(),
+                bytes_sent: counter.bytes_sent() as i64,
+                bytes_recv: counter.bytes_recv() as i64,
+                packets_sent: counter.packets_sent() as i64,
+                packets_recv: counter.packets_recv() as i64,
+                errors_sent: counter.errors_sent() as i64,
+                errors_recv: counter.errors_recv() as i64,
+                drop_sent: drop_sent(&counter),
+                drop_recv: counter.drop_recv() as i64,
+            })?;
+        }
+        Ok(())
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn drop_sent(counter: &IoCounters) -> i64 {
+    use heim::net::os::linux::IoCountersExt;
+    counter.drop_sent() as i64
+}
+
+#[cfg(not(target_os = "linux"))]
+fn drop_sent(_: &IoCounters) -> i64 {
+    0
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<NetIO>().unwrap();
+        if let Err(err) = net_io(&mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+        drop(req);
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "interface", Integer: "bytes_sent", Integer: "bytes_recv", Integer: "packets_sent", Integer: "packets_recv", Integer: "errors_sent", Integer: "errors_recv", Integer: "drop_sent", Integer: "drop_recv"],
+        resp.columns()
+    );
+
+    let mut index = 0;
+    for row in resp {
+        let _ = row.unwrap();
+        index += 1;
+    }
+    assert!(index > 0);
+}
@@ -1,13 +1,6 @@
-use crate::{Columns, Promise, Result, Row, ToData};
+use crate::{datasource::OSInfo, Promise, Result, ToData};
 use heim::host::{platform, Platform};
 
-#[derive(Data)]
-pub struct OSInfo {
-    os_type: String,
-    version: String,
-    host_name: String,
-}
-
 #[datasource]
 pub fn os_info(promise: &mut Promise<OSInfo>) -> Result<()> {
     let platform: Platform = async_std::task::block_on(platform())?;
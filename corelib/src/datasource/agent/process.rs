@@ -0,0 +1,138 @@
+use crate::{Columns, Error, Promise, Row, ToData, Value};
+
+use super::format;
+use async_std::prelude::*;
+use async_std::task::block_on;
+use heim::process::{processes, Pid};
+use heim::units::{time, Time};
+
+#[derive(Data)]
+pub struct ProcessInfo {
+    pub pid: i64,
+    pub ppid: i64,
+    pub name: String,
+    pub cmdline: String,
+    pub user: String,
+    pub state: String,
+    pub rss_bytes: i64,
+    pub vms_bytes: i64,
+    pub cpu_time: f64,
+    pub start_time: i64,
+}
+
+#[datasource(filter(pid, ppid, name, user))]
+pub fn process(promise: &mut Promise<ProcessInfo>) -> Result<(), Error> {
+    block_on(async {
+        let mut processes = Box::pin(processes());
+        while let Some(process) = processes.next().await {
+            // 进程可能在遍历过程中退出，忽略该进程
+            let process = match process {
+                Ok(process) => process,
+                Err(_) => continue,
+            };
+            let pid: Pid = process.pid();
+            // 提前过滤，避免读取不需要的进程信息
+            if !promise.get_filters().matches("pid", &Value::from(pid as i64)) {
+                continue;
+            }
+            let name = match process.name().await {
+                Ok(name) => name,
+                Err(_) => continue,
+            };
+            if !promise.get_filters().matches("name", &Value::from(name.as_str())) {
+                continue;
+            }
+
+            let ppid = process.parent_pid().await.unwrap_or(0) as i64;
+            let cmdline = process
+                .command()
+                .await
+                .map(|cmd| cmd.to_os_string().to_string_lossy().trim().to_string())
+                .unwrap_or_default();
+            let state = process
+                .status()
+                .await
+                .map(|status| format!("{:?}", status))
+                .unwrap_or_default();
+            let (rss_bytes, vms_bytes) = process
+                .memory()
+                .await
+                .map(|memory| (memory.rss() as i64, memory.vms() as i64))
+                .unwrap_or((0, 0));
+            let cpu_time = process
+                .cpu_time()
+                .await
+                .map(|cpu| seconds(cpu.user()) + seconds(cpu.system()))
+                .unwrap_or(0.0);
+            let start_time = process
+                .create_time()
+                .await
+                .map(|t| format(t) as i64)
+                .unwrap_or(0);
+
+            promise.commit(ProcessInfo {
+                pid: pid as i64,
+                ppid,
+                name,
+                cmdline,
+                user: process_user(pid),
+                state,
+                rss_bytes,
+                vms_bytes,
+                cpu_time,
+                start_time,
+            })?;
+        }
+        Ok(())
+    })
+}
+
+fn seconds(t: Time) -> f64 {
+    t.get::<time::second>()
+}
+
+/// 获取进程的所属用户，Linux 下通过 `/proc/${pid}` 的属主来确定
+#[cfg(target_os = "linux")]
+fn process_user(pid: Pid) -> String {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(format!("/proc/{}", pid))
+        .map(|meta| super::user_name(meta.uid()))
+        .unwrap_or_default()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_user(_: Pid) -> String {
+    String::new()
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(10));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<ProcessInfo>().unwrap();
+        if let Err(err) = process(&mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+        drop(req);
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![Integer: "pid", Integer: "ppid", String: "name", String: "cmdline", String: "user", String: "state", Integer: "rss_bytes", Integer: "vms_bytes", Number: "cpu_time", Integer: "start_time"],
+        resp.columns()
+    );
+
+    let current = std::process::id() as i64;
+    let mut has_current = false;
+    for row in resp {
+        let row = row.unwrap();
+        let pid: i64 = row.get(0).unwrap();
+        if pid == current {
+            has_current = true;
+        }
+    }
+    assert!(has_current);
+}
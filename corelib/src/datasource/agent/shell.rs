@@ -1,18 +1,35 @@
 use crate::datasource::BashRow;
-use crate::{Instance, Promise, Result, ToData, ToType};
-use std::io::Read;
-use std::process::Command;
+use crate::{Error, Instance, Promise, Result, ToData, ToType};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, Command};
+use std::sync::mpsc::{sync_channel, RecvTimeoutError, SyncSender};
 use std::sync::Arc;
-use std::time::Duration;
-use timeout_readwrite::TimeoutReadExt;
+use std::time::{Duration, Instant};
 
-#[datasource]
+/// 尚未提交的输出行的最大数量，超过后读取线程会阻塞，子进程的输出也随之阻塞
+const LINE_BUFFER: usize = 1024;
+
+/// 等待输出时检查请求是否被取消的间隔
+const CANCEL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Copy)]
+enum Stream {
+    Stdout,
+    Stderr,
+}
+
+#[datasource(filter(line_num, stream))]
 fn shell(
     instance: Arc<Instance>,
     script: String,
     timeout: u32,
+    cwd: Option<String>,
+    user: Option<String>,
+    input: Option<String>,
+    all_streams: Option<bool>,
     promise: &mut Promise<BashRow>,
 ) -> Result<()> {
+    let all_streams = all_streams.unwrap_or(false);
     let mut cmd = if cfg!(target_os = "windows") {
         let mut cmd = Command::new("cmd");
         cmd.args(&["/C", &script]);
@@ -26,33 +43,327 @@ fn shell(
     for (key, val) in instance.environments.iter() {
         cmd.env(key, val);
     }
+    if let Some(cwd) = &cwd {
+        cmd.current_dir(cwd);
+    }
+    if let Some(user) = &user {
+        run_as(&mut cmd, user)?;
+    }
+    if input.is_some() {
+        cmd.stdin(std::process::Stdio::piped());
+    }
+    new_process_group(&mut cmd);
 
-    let child = cmd
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::null())
-        .spawn()?;
-    let stdout = child.stdout.ok_or(std::io::Error::new(
+    let start = Instant::now();
+    let deadline = start + Duration::from_secs(timeout as u64);
+    let mut child = ChildGuard::new(
+        cmd.stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()?,
+    );
+    let stdout = child.stdout.take().ok_or(std::io::Error::new(
         std::io::ErrorKind::InvalidInput,
         "lose stdout",
     ))?;
+    let stderr = child.stderr.take().ok_or(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        "lose stderr",
+    ))?;
+    if let Some(input) = input {
+        let mut stdin = child.stdin.take().ok_or(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "lose stdin",
+        ))?;
+        // 输入内容较多时写入会阻塞，需要与读取输出同时进行，写入完成后关闭标准输入
+        let _ = std::thread::spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        });
+    }
+
+    // 标准输出和标准错误分别在单独的线程中按行读取，读取到一行就立即提交
+    let (tx, rx) = sync_channel(LINE_BUFFER);
+    read_lines(stdout, Stream::Stdout, tx.clone());
+    read_lines(stderr, Stream::Stderr, tx);
+
+    let (mut stdout_num, mut stderr_num) = (0, 0);
+    loop {
+        // 请求被取消后立即返回，子进程守卫会终止整个进程组
+        if promise.is_cancelled() {
+            return Err(cancelled_error(&script));
+        }
+        let wait = deadline.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(wait.min(CANCEL_INTERVAL)) {
+            Ok((Stream::Stdout, line)) => {
+                promise.commit(BashRow::stdout(line, stdout_num))?;
+                stdout_num += 1;
+            }
+            // 标准错误默认只读取不返回，避免阻塞子进程
+            Ok((Stream::Stderr, line)) => {
+                if all_streams {
+                    promise.commit(BashRow::stderr(line, stderr_num))?;
+                }
+                stderr_num += 1;
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                if Instant::now() >= deadline {
+                    return Err(timeout_error(&script, timeout, &mut child));
+                }
+            }
+            // 两个输出流都已经关闭
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    // 输出流关闭后子进程仍可能在运行
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            return Err(timeout_error(&script, timeout, &mut child));
+        }
+        if promise.is_cancelled() {
+            return Err(cancelled_error(&script));
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    };
+    let elapsed = start.elapsed();
+
+    // 被信号终止的进程没有退出码
+    if all_streams {
+        promise.commit(BashRow::status(
+            status.code().map(i64::from).unwrap_or(-1),
+            elapsed,
+        ))?;
+    }
+    Ok(())
+}
+
+/// 在单独的线程中按行读取输出流
+fn read_lines<R: Read + Send + 'static>(
+    reader: R,
+    stream: Stream,
+    tx: SyncSender<(Stream, String)>,
+) {
+    let _ = std::thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut line = vec![];
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    if line.ends_with(b"\n") {
+                        line.pop();
+                    }
+                    if line.ends_with(b"\r") {
+                        line.pop();
+                    }
+                    let line = String::from_utf8_lossy(&line).to_string();
+                    // 接收端已经退出
+                    if tx.send((stream, line)).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// 终止进程组，返回超时错误
+fn timeout_error(script: &str, timeout: u32, child: &mut ChildGuard) -> Error {
+    let pid = child.id();
+    child.kill();
+    Error::io_timeout(format!(
+        "cmd - [{}] is timeout in {} s, process group {} is killed",
+        script, timeout, pid
+    ))
+}
+
+/// 请求被取消的错误，子进程由守卫负责终止
+fn cancelled_error(script: &str) -> Error {
+    Error::cancelled(&format!(
+        "cmd - [{}] is cancelled, process group is killed",
+        script
+    ))
+}
+
+/// 子进程守卫，子进程未正常结束时 (超时或者提前返回) 会终止其整个进程组
+struct ChildGuard {
+    child: Child,
+    finished: bool,
+}
+
+impl ChildGuard {
+    fn new(child: Child) -> Self {
+        Self {
+            child,
+            finished: false,
+        }
+    }
+
+    /// 检查子进程是否已经结束，结束后不再终止其进程组，命令有意在后台运行的进程不受影响
+    fn try_wait(&mut self) -> std::io::Result<Option<std::process::ExitStatus>> {
+        let status = self.child.try_wait()?;
+        if status.is_some() {
+            self.finished = true;
+        }
+        Ok(status)
+    }
+
+    /// 终止子进程及其创建的所有进程，并回收子进程
+    fn kill(&mut self) {
+        if self.finished {
+            return;
+        }
+        kill_process_group(&mut self.child);
+        let _ = self.child.wait();
+        self.finished = true;
+    }
+}
+
+impl std::ops::Deref for ChildGuard {
+    type Target = Child;
+    fn deref(&self) -> &Child {
+        &self.child
+    }
+}
+
+impl std::ops::DerefMut for ChildGuard {
+    fn deref_mut(&mut self) -> &mut Child {
+        &mut self.child
+    }
+}
+
+impl Drop for ChildGuard {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+/// 在新的进程组中执行命令，进程组 ID 与子进程 ID 相同
+#[cfg(unix)]
+fn new_process_group(cmd: &mut Command) {
+    use std::os::unix::process::CommandExt;
+    unsafe {
+        cmd.pre_exec(|| {
+            if libc::setpgid(0, 0) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
 
-    let mut data = String::new();
-    let _ = stdout
-        .with_timeout(Duration::from_secs(timeout as u64))
-        .read_to_string(&mut data)?;
+#[cfg(not(unix))]
+fn new_process_group(_: &mut Command) {}
 
-    let mut cur = data.lines();
-    let mut index = 0;
-    while let Some(line) = cur.next() {
-        promise.commit(BashRow {
-            line: line.to_owned(),
-            line_num: index,
-        })?;
-        index += 1;
+/// 以指定用户执行命令，切换为其他用户时需要以 root 用户运行
+#[cfg(unix)]
+fn run_as(cmd: &mut Command, user: &str) -> Result<()> {
+    use std::os::unix::process::CommandExt;
+    let (uid, gid, home) = lookup_user(user)?;
+    let euid = unsafe { libc::geteuid() };
+    if uid != euid {
+        if euid != 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!("run as user {} requires root", user),
+            )
+            .into());
+        }
+        let groups = user_groups(user, gid)?;
+        // 需要同时设置附属组，否则 oracle 等用户会丢失 dba 等用户组的权限
+        unsafe {
+            cmd.pre_exec(move || {
+                if libc::setgroups(groups.len() as _, groups.as_ptr()) != 0
+                    || libc::setgid(gid) != 0
+                    || libc::setuid(uid) != 0
+                {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
     }
+    cmd.env("USER", user).env("LOGNAME", user).env("HOME", home);
     Ok(())
 }
 
+#[cfg(not(unix))]
+fn run_as(_: &mut Command, user: &str) -> Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Other,
+        format!("run as user {} is only supported on unix", user),
+    )
+    .into())
+}
+
+/// 通过用户名获取 uid、gid 以及用户主目录
+#[cfg(unix)]
+fn lookup_user(user: &str) -> Result<(libc::uid_t, libc::gid_t, String)> {
+    use std::ffi::{CStr, CString};
+    let name = CString::new(user).map_err(std::io::Error::from)?;
+    let mut buf = vec![0 as libc::c_char; 4096];
+    let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    let rs = unsafe {
+        libc::getpwnam_r(
+            name.as_ptr(),
+            &mut pwd,
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut result,
+        )
+    };
+    if rs != 0 || result.is_null() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("user {} is not found", user),
+        )
+        .into());
+    }
+    let home = unsafe { CStr::from_ptr(pwd.pw_dir) }
+        .to_string_lossy()
+        .to_string();
+    Ok((pwd.pw_uid, pwd.pw_gid, home))
+}
+
+/// 获取用户所属的所有用户组
+#[cfg(target_os = "linux")]
+fn user_groups(user: &str, gid: libc::gid_t) -> Result<Vec<libc::gid_t>> {
+    use std::ffi::CString;
+    let name = CString::new(user).map_err(std::io::Error::from)?;
+    let mut count: libc::c_int = 64;
+    loop {
+        let mut groups = vec![0 as libc::gid_t; count as usize];
+        let rs = unsafe { libc::getgrouplist(name.as_ptr(), gid, groups.as_mut_ptr(), &mut count) };
+        if rs >= 0 {
+            groups.truncate(count as usize);
+            return Ok(groups);
+        }
+        // 用户组数量超过缓冲区大小时，count 会被设置为实际的数量
+        count = count.max(groups.len() as libc::c_int * 2);
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn user_groups(_: &str, gid: libc::gid_t) -> Result<Vec<libc::gid_t>> {
+    Ok(vec![gid])
+}
+
+#[cfg(unix)]
+fn kill_process_group(child: &mut Child) {
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(child: &mut Child) {
+    let _ = child.kill();
+}
+
 #[test]
 fn test() {
     use crate::*;
@@ -65,8 +376,12 @@ fn test() {
         let mut promise = req.head::<BashRow>().unwrap();
         if let Err(err) = shell(
             Arc::new(instance),
-            "echo $ORACLE_HOME".to_string(),
+            "echo $ORACLE_HOME;sleep 0.1;echo error >&2;exit 3".to_string(),
             2,
+            None,
+            None,
+            None,
+            Some(true),
             &mut promise,
         ) {
             let _ = req.error(err);
@@ -77,25 +392,61 @@ fn test() {
 
     let resp = resp.wait().unwrap();
     assert_eq!(
-        &columns![String: "line",Integer: "line_num"],
+        &columns![String: "line", Integer: "line_num", String: "stream", Integer: "exit_code", Integer: "elapsed_ms"],
         resp.columns()
     );
 
-    let mut index = 0;
+    let mut rows = vec![];
     for row in resp {
         let row = row.unwrap();
         let line: String = row.get(0).unwrap();
         let line_num: i64 = row.get(1).unwrap();
-
-        assert_eq!("/app/u01/12c".to_owned(), line);
-        assert_eq!(0, line_num);
-        index += 1;
+        let stream: String = row.get(2).unwrap();
+        rows.push((line, line_num, stream, row.get::<i64>(3).ok()));
     }
-    assert!(index > 0);
+    assert_eq!(
+        vec![
+            ("/app/u01/12c".to_owned(), 0, "stdout".to_owned(), None),
+            ("error".to_owned(), 0, "stderr".to_owned(), None),
+            ("".to_owned(), 0, "status".to_owned(), Some(3)),
+        ],
+        rows
+    );
 }
 
 #[test]
-#[should_panic(expected = "timed out")]
+fn test_shell_stream() {
+    use crate::*;
+    let instance = Instance::from("sqlite:agent:default").unwrap();
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(10));
+    let handle = std::thread::spawn(move || {
+        let mut promise = req.head::<BashRow>().unwrap();
+        shell(
+            Arc::new(instance),
+            "echo first;sleep 1;echo second".to_string(),
+            10,
+            None,
+            None,
+            None,
+            None,
+            &mut promise,
+        )
+    });
+
+    // 第一行在命令执行结束前就已经返回
+    let start = Instant::now();
+    let mut resp = resp.wait().unwrap();
+    let line: String = resp.next().unwrap().unwrap().get(0).unwrap();
+    assert_eq!("first", line);
+    assert!(start.elapsed() < Duration::from_millis(800));
+
+    // 结果集被丢弃后，数据源返回错误
+    drop(resp);
+    assert!(handle.join().unwrap().is_err());
+}
+
+#[test]
+#[should_panic(expected = "is killed")]
 fn test_shell_timeout() {
     use crate::*;
     let instance = Instance::from(
@@ -110,6 +461,10 @@ fn test_shell_timeout() {
             Arc::new(instance),
             "sleep 10;echo 'Hello world'".to_string(),
             2,
+            None,
+            None,
+            None,
+            None,
             &mut promise,
         ) {
             let _ = req.error(err);
@@ -119,11 +474,197 @@ fn test_shell_timeout() {
     }
 
     let resp = resp.wait().unwrap();
-    assert_eq!(
-        &columns![String: "line",Integer: "line_num"],
-        resp.columns()
-    );
     for row in resp {
         let _ = row.unwrap();
     }
 }
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_shell_timeout_kill() {
+    use crate::*;
+    const PID_FILE: &str = "/tmp/test_shell_timeout_kill";
+    let _ = std::fs::remove_file(PID_FILE);
+    let instance = Instance::from("sqlite:agent:default").unwrap();
+    let (req, _resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    let mut promise = req.head::<BashRow>().unwrap();
+    let err = shell(
+        Arc::new(instance),
+        format!("sleep 10 & echo $! > {}; wait", PID_FILE),
+        1,
+        None,
+        None,
+        None,
+        None,
+        &mut promise,
+    )
+    .err()
+    .unwrap();
+    assert!(format!("{}", err).contains("is killed"));
+
+    // 后台的 sleep 进程也会被终止
+    let pid = std::fs::read_to_string(PID_FILE).unwrap();
+    std::thread::sleep(Duration::from_millis(200));
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid.trim())).unwrap_or_default();
+    assert!(stat.is_empty() || stat.contains(") Z "));
+    std::fs::remove_file(PID_FILE).unwrap();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_shell_background() {
+    use crate::*;
+    const PID_FILE: &str = "/tmp/test_shell_background";
+    let _ = std::fs::remove_file(PID_FILE);
+    let instance = Instance::from("sqlite:agent:default").unwrap();
+    let (req, _resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    let mut promise = req.head::<BashRow>().unwrap();
+    shell(
+        Arc::new(instance),
+        format!(
+            "nohup sleep 10 >/dev/null 2>&1 & echo $! > {}; exit 0",
+            PID_FILE
+        ),
+        2,
+        None,
+        None,
+        None,
+        None,
+        &mut promise,
+    )
+    .unwrap();
+
+    // 命令正常结束后，有意在后台运行的进程不会被终止
+    let pid = std::fs::read_to_string(PID_FILE).unwrap();
+    let pid: libc::pid_t = pid.trim().parse().unwrap();
+    std::thread::sleep(Duration::from_millis(200));
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).unwrap_or_default();
+    assert!(!stat.is_empty() && !stat.contains(") Z "));
+    unsafe {
+        libc::kill(pid, libc::SIGKILL);
+    }
+    std::fs::remove_file(PID_FILE).unwrap();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_shell_cancel() {
+    use crate::*;
+    const PID_FILE: &str = "/tmp/test_shell_cancel";
+    let _ = std::fs::remove_file(PID_FILE);
+    let instance = Instance::from("sqlite:agent:default").unwrap();
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    let handle = std::thread::spawn(move || {
+        let mut promise = req.head::<BashRow>().unwrap();
+        shell(
+            Arc::new(instance),
+            format!("sleep 10 & echo $! > {}; echo started; wait", PID_FILE),
+            10,
+            None,
+            None,
+            None,
+            None,
+            &mut promise,
+        )
+    });
+
+    let mut resp = resp.wait().unwrap();
+    let line: String = resp.next().unwrap().unwrap().get(0).unwrap();
+    assert_eq!("started", line);
+
+    // 结果集被释放后，数据源不必等待新的输出就会返回，并终止整个进程组
+    let start = Instant::now();
+    drop(resp);
+    let err = handle.join().unwrap().err().unwrap();
+    assert!(err.is_cancelled());
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    let pid = std::fs::read_to_string(PID_FILE).unwrap();
+    std::thread::sleep(Duration::from_millis(200));
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid.trim())).unwrap_or_default();
+    assert!(stat.is_empty() || stat.contains(") Z "));
+    std::fs::remove_file(PID_FILE).unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn test_shell_options() {
+    use crate::*;
+    let instance = Arc::new(Instance::from("sqlite:agent:default").unwrap());
+    let user = super::user_name(unsafe { libc::geteuid() });
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<BashRow>().unwrap();
+        if let Err(err) = shell(
+            instance,
+            "pwd;id -un;cat".to_string(),
+            2,
+            Some("/tmp".to_owned()),
+            Some(user),
+            Some("Hello world".to_owned()),
+            None,
+            &mut promise,
+        ) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let lines: Vec<String> = resp
+        .wait()
+        .unwrap()
+        .map(|row| row.unwrap().get(0).unwrap())
+        .collect();
+    let user = super::user_name(unsafe { libc::geteuid() });
+    assert_eq!(
+        vec!["/tmp".to_owned(), user, "Hello world".to_owned()],
+        lines
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn test_shell_user_not_found() {
+    let err = run_as(&mut Command::new("sh"), "bee_not_exists")
+        .err()
+        .unwrap();
+    assert!(err.get_msg().contains("not found"));
+}
+
+#[test]
+#[cfg(unix)]
+fn test_shell_switch_user() {
+    use crate::*;
+    // 只有 root 用户可以切换为其他用户
+    if unsafe { libc::geteuid() } != 0 || lookup_user("nobody").is_err() {
+        return;
+    }
+    let instance = Arc::new(Instance::from("sqlite:agent:default").unwrap());
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<BashRow>().unwrap();
+        if let Err(err) = shell(
+            instance,
+            "id -un;echo $HOME".to_string(),
+            2,
+            None,
+            Some("nobody".to_owned()),
+            None,
+            None,
+            &mut promise,
+        ) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let lines: Vec<String> = resp
+        .wait()
+        .unwrap()
+        .map(|row| row.unwrap().get(0).unwrap())
+        .collect();
+    let (_, _, home) = lookup_user("nobody").unwrap();
+    assert_eq!(vec!["nobody".to_owned(), home], lines);
+}
@@ -0,0 +1,79 @@
+use crate::datasource::{commit_lines, tail_offset, FileLine};
+use crate::{Promise, Result, ToData, ToType};
+use std::fs::{File, Metadata};
+use std::io::{BufReader, Seek, SeekFrom};
+
+#[datasource]
+pub fn tail_file(
+    path: String,
+    from_offset: i64,
+    max_lines: Option<u32>,
+    last_inode: Option<i64>,
+    promise: &mut Promise<FileLine>,
+) -> Result<()> {
+    let mut file = File::open(&path)?;
+    let meta = file.metadata()?;
+    let inode = inode(&meta);
+    let (offset, skip_partial) = tail_offset(from_offset, meta.len(), inode, last_inode);
+    debug!(
+        "tail file [{}] from {} with inode = {}",
+        path, offset, inode
+    );
+
+    file.seek(SeekFrom::Start(offset))?;
+    commit_lines(
+        BufReader::new(file),
+        offset,
+        skip_partial,
+        inode,
+        max_lines,
+        promise,
+    )
+}
+
+#[cfg(unix)]
+fn inode(meta: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    meta.ino()
+}
+
+#[cfg(not(unix))]
+fn inode(_: &Metadata) -> u64 {
+    0
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    const PATH: &str = "/tmp/test_tail_file.log";
+    std::fs::write(&PATH, "first\nsecond\nthird\npartial").unwrap();
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<FileLine>().unwrap();
+        if let Err(err) = tail_file(PATH.to_string(), 6, None, None, &mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "line", Integer: "line_num", Integer: "byte_offset", Integer: "inode"],
+        resp.columns()
+    );
+
+    let mut lines = vec![];
+    for row in resp {
+        let row = row.unwrap();
+        let line: String = row.get(0).unwrap();
+        let line_num: i64 = row.get(1).unwrap();
+        let byte_offset: i64 = row.get(2).unwrap();
+        assert_eq!(lines.len() as i64, line_num);
+        lines.push((line, byte_offset));
+    }
+    assert_eq!(
+        vec![("second".to_owned(), 13), ("third".to_owned(), 19)],
+        lines
+    );
+}
@@ -1,17 +1,23 @@
 use crate::{BashRow, Promise, Result, ToData, ToType};
+use std::time::Duration;
 
-#[datasource]
-fn shell(output: String, _timeout: u32, promise: &mut Promise<BashRow>) -> Result<()> {
+#[datasource(filter(line_num, stream))]
+fn shell(
+    output: String,
+    _timeout: u32,
+    all_streams: Option<bool>,
+    promise: &mut Promise<BashRow>,
+) -> Result<()> {
     let lines = output.lines();
 
     let mut index = 0;
     for line in lines {
-        promise.commit(BashRow {
-            line: line.to_string(),
-            line_num: index,
-        })?;
+        promise.commit(BashRow::stdout(line.to_string(), index))?;
         index += 1;
     }
+    if all_streams.unwrap_or(false) {
+        promise.commit(BashRow::status(0, Duration::from_millis(0)))?;
+    }
     Ok(())
 }
 
@@ -28,6 +34,7 @@ fn test() {
             "#
             .to_owned(),
             10,
+            None,
             &mut promise,
         ) {
             let _ = req.error(err);
@@ -39,7 +46,7 @@ fn test() {
 
     let resp = resp.wait().unwrap();
     assert_eq!(
-        &columns![String: "line",Integer: "line_num"],
+        &columns![String: "line", Integer: "line_num", String: "stream", Integer: "exit_code", Integer: "elapsed_ms"],
         resp.columns()
     );
 
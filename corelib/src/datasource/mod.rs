@@ -12,10 +12,337 @@ pub struct Status {
     pub success: bool,
 }
 
+/// 脚本的输出行，脚本执行结束后会追加一行 `stream` 为 `status` 的状态行
 #[derive(Data)]
 pub struct BashRow {
     line: String,
     line_num: u32,
+    stream: String,
+    exit_code: Option<i64>,
+    elapsed_ms: Option<i64>,
+}
+
+impl BashRow {
+    /// 标准输出中的一行
+    pub fn stdout(line: String, line_num: u32) -> Self {
+        Self::output("stdout", line, line_num)
+    }
+
+    /// 标准错误中的一行
+    pub fn stderr(line: String, line_num: u32) -> Self {
+        Self::output("stderr", line, line_num)
+    }
+
+    /// 脚本的退出码以及执行耗时
+    pub fn status(exit_code: i64, elapsed: std::time::Duration) -> Self {
+        Self {
+            line: String::new(),
+            line_num: 0,
+            stream: "status".to_owned(),
+            exit_code: Some(exit_code),
+            elapsed_ms: Some(elapsed.as_millis() as i64),
+        }
+    }
+
+    fn output(stream: &str, line: String, line_num: u32) -> Self {
+        Self {
+            line,
+            line_num,
+            stream: stream.to_owned(),
+            exit_code: None,
+            elapsed_ms: None,
+        }
+    }
+}
+
+#[derive(Data)]
+pub struct HostBasic {
+    pub host_name: String,
+    pub cpu_core: i64,
+    pub cpu_model: String,
+    pub uptime: i64,
+    pub memory: i64,
+}
+
+#[derive(Data, PartialEq)]
+pub struct CPUUsage {
+    idle: f64,
+    user: f64,
+    system: f64,
+    iowait: f64,
+}
+
+#[derive(Data)]
+pub struct OSInfo {
+    os_type: String,
+    version: String,
+    host_name: String,
+}
+
+#[derive(Data)]
+pub struct MemoryUsage {
+    used_bytes: i64,
+    total_bytes: i64,
+    free_bytes: i64,
+}
+
+#[derive(Data)]
+pub struct SWAPUsage {
+    used_bytes: i64,
+    total_bytes: i64,
+    free_bytes: i64,
+}
+
+#[derive(Data)]
+pub struct Filesystem {
+    pub name: String,
+    pub mount_on: String,
+    pub total_bytes: i64,
+    pub used_bytes: i64,
+    pub free_bytes: i64,
+    pub fs_type: String,
+    pub options: String,
+    pub inodes_total: i64,
+    pub inodes_used: i64,
+    pub inodes_free: i64,
+    pub read_only: bool,
+}
+
+/// 还原 `/proc/mounts` 中被转义为 `\040` 形式的字符
+#[cfg(any(all(feature = "agent", target_os = "linux"), feature = "remote"))]
+fn unescape(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut rs = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'\\' && index + 3 < bytes.len() {
+            if let Some(Ok(ch)) = value
+                .get(index + 1..index + 4)
+                .map(|oct| u8::from_str_radix(oct, 8))
+            {
+                rs.push(ch);
+                index += 4;
+                continue;
+            }
+        }
+        rs.push(bytes[index]);
+        index += 1;
+    }
+    String::from_utf8_lossy(&rs).to_string()
+}
+
+/// 目录中的文件信息
+#[derive(Data)]
+pub struct FileEntry {
+    name: String,
+    path: String,
+    file_type: String,
+    size: i64,
+    mode: String,
+    owner: String,
+    group: String,
+    mtime: i64,
+    atime: i64,
+    ctime: i64,
+    link_target: String,
+}
+
+/// 文件传输的结果
+#[derive(Data)]
+pub struct FileTransfer {
+    source: String,
+    target: String,
+    size: i64,
+}
+
+/// 按照文件名匹配到的文件
+#[derive(Data)]
+pub struct FoundFile {
+    path: String,
+    name: String,
+    size: i64,
+    mtime: i64,
+}
+
+/// 将文件名匹配模式转换为正则表达式
+///
+/// 默认为通配符模式，支持 `*`、`?` 以及 `[...]`，以 `re:` 开头时作为正则表达式
+#[cfg(any(feature = "agent", feature = "remote"))]
+fn name_matcher(pattern: &str) -> Result<regex::Regex> {
+    let re = if let Some(re) = pattern.strip_prefix("re:") {
+        re.to_owned()
+    } else {
+        let mut re = String::from("^");
+        let mut in_class = false;
+        for ch in pattern.chars() {
+            match ch {
+                '*' if !in_class => re.push_str(".*"),
+                '?' if !in_class => re.push('.'),
+                '[' if !in_class => {
+                    in_class = true;
+                    re.push('[');
+                }
+                ']' if in_class => {
+                    in_class = false;
+                    re.push(']');
+                }
+                '!' if in_class && re.ends_with('[') => re.push('^'),
+                '\\' | '^' | '[' | '&' | '~' if in_class => {
+                    re.push('\\');
+                    re.push(ch);
+                }
+                _ if in_class => re.push(ch),
+                _ => re.push_str(&regex::escape(&ch.to_string())),
+            }
+        }
+        re.push('$');
+        re
+    };
+    regex::Regex::new(&re)
+        .map_err(|err| crate::Error::invalid_type(format!("invalid pattern {} - {}", pattern, err)))
+}
+
+/// 文件的摘要信息
+#[derive(Data)]
+pub struct FileHash {
+    path: String,
+    algorithm: String,
+    hash: String,
+    size: i64,
+}
+
+/// 流式读取内容并计算摘要，返回十六进制的摘要值以及读取的字节数
+///
+/// 支持的算法包括 md5、sha1、sha256 以及 crc32
+#[cfg(any(feature = "agent", feature = "remote"))]
+fn hash_reader<R: std::io::Read>(mut reader: R, algorithm: &str) -> Result<(String, u64)> {
+    use sha2::Digest;
+
+    enum Hasher {
+        Md5(md5::Md5),
+        Sha1(sha1::Sha1),
+        Sha256(sha2::Sha256),
+        Crc32(crc32fast::Hasher),
+    }
+
+    let mut hasher = match algorithm.to_lowercase().as_str() {
+        "md5" => Hasher::Md5(md5::Md5::new()),
+        "sha1" => Hasher::Sha1(sha1::Sha1::new()),
+        "sha256" => Hasher::Sha256(sha2::Sha256::new()),
+        "crc32" => Hasher::Crc32(crc32fast::Hasher::new()),
+        _ => {
+            return Err(crate::Error::invalid_type(format!(
+                "unsupported hash algorithm - {}",
+                algorithm
+            )))
+        }
+    };
+
+    let mut size = 0;
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let len = reader.read(&mut buffer)?;
+        if len == 0 {
+            break;
+        }
+        let data = &buffer[0..len];
+        match &mut hasher {
+            Hasher::Md5(hasher) => Digest::update(hasher, data),
+            Hasher::Sha1(hasher) => Digest::update(hasher, data),
+            Hasher::Sha256(hasher) => Digest::update(hasher, data),
+            Hasher::Crc32(hasher) => hasher.update(data),
+        }
+        size += len as u64;
+    }
+
+    let to_hex = |bytes: &[u8]| -> String { bytes.iter().map(|b| format!("{:02x}", b)).collect() };
+    let hash = match hasher {
+        Hasher::Md5(hasher) => to_hex(&hasher.finalize()),
+        Hasher::Sha1(hasher) => to_hex(&hasher.finalize()),
+        Hasher::Sha256(hasher) => to_hex(&hasher.finalize()),
+        Hasher::Crc32(hasher) => format!("{:08x}", hasher.finalize()),
+    };
+    Ok((hash, size))
+}
+
+/// 按行读取的文件内容
+#[derive(Data)]
+pub struct FileLine {
+    line: String,
+    line_num: u32,
+    byte_offset: i64,
+    inode: i64,
+}
+
+/// 计算 tail_file 的读取位置，返回开始读取的位置以及是否需要跳过第一个不完整的行
+///
+/// - 文件的 inode 与上次读取时不一致时，说明文件已被轮转，从头开始读取
+/// - 读取位置超过文件大小时，说明文件已被截断，从头开始读取
+/// - 读取位置为负数时从文件末尾开始计算
+#[cfg(any(feature = "agent", feature = "remote"))]
+fn tail_offset(
+    from_offset: i64,
+    file_size: u64,
+    inode: u64,
+    last_inode: Option<i64>,
+) -> (u64, bool) {
+    if last_inode.map(|last| last as u64 != inode).unwrap_or(false) {
+        return (0, false);
+    }
+    if from_offset < 0 {
+        let start = file_size.saturating_sub(from_offset.unsigned_abs());
+        // 从前一个字节开始读取，用于判断开始位置是否正好为行首
+        return if start > 0 {
+            (start - 1, true)
+        } else {
+            (0, false)
+        };
+    }
+    if from_offset as u64 > file_size {
+        return (0, false);
+    }
+    (from_offset as u64, false)
+}
+
+/// 从 `offset` 开始按行提交内容，不以换行符结尾的最后一行不会提交，以便下次从该行的行首继续读取
+#[cfg(any(feature = "agent", feature = "remote"))]
+fn commit_lines<R: std::io::BufRead>(
+    mut reader: R,
+    mut offset: u64,
+    skip_partial: bool,
+    inode: u64,
+    max_lines: Option<u32>,
+    promise: &mut crate::Promise<FileLine>,
+) -> Result<()> {
+    let mut buffer = vec![];
+    if skip_partial {
+        let size = reader.read_until(b'\n', &mut buffer)?;
+        if !buffer.ends_with(b"\n") {
+            return Ok(());
+        }
+        offset += size as u64;
+    }
+
+    let max_lines = max_lines.unwrap_or(u32::MAX);
+    let mut line_num = 0;
+    while line_num < max_lines {
+        buffer.clear();
+        let size = reader.read_until(b'\n', &mut buffer)?;
+        if size == 0 || !buffer.ends_with(b"\n") {
+            break;
+        }
+        offset += size as u64;
+
+        let line = String::from_utf8_lossy(&buffer);
+        promise.commit(FileLine {
+            line: line.trim_end_matches(&['\r', '\n'][..]).to_owned(),
+            line_num,
+            byte_offset: offset as i64,
+            inode: inode as i64,
+        })?;
+        line_num += 1;
+    }
+    Ok(())
 }
 
 pub trait DataSource: Send + Sync {
@@ -24,6 +351,11 @@ pub trait DataSource: Send + Sync {
     fn columns(&self) -> Columns;
     fn get_register(&self) -> &Register;
     fn collect(&self, request: &mut Request) -> Result<()>;
+
+    /// 可以下推过滤条件的结果列，过滤条件通过 `Request::get_filters` 传递给数据源
+    fn filter_columns(&self) -> Vec<&str> {
+        vec![]
+    }
 }
 
 /// 注册数据源
@@ -47,3 +379,90 @@ pub fn register_ds<T: Configure>(instance: &Instance, connection: &T) -> Result<
     }
     Ok(())
 }
+
+#[test]
+#[cfg(any(feature = "agent", feature = "remote"))]
+fn test_name_matcher() {
+    let re = name_matcher("alert_*.log").unwrap();
+    assert!(re.is_match("alert_orcl.log"));
+    assert!(!re.is_match("alert_orcl.log.1"));
+    assert!(!re.is_match("xalert_orcl.log"));
+
+    let re = name_matcher("file?.[!a-c]*").unwrap();
+    assert!(re.is_match("file1.dat"));
+    assert!(!re.is_match("file1.bat"));
+
+    let re = name_matcher("re:^orcl_(ora|lgwr)_[0-9]+\\.trc$").unwrap();
+    assert!(re.is_match("orcl_ora_1234.trc"));
+    assert!(!re.is_match("orcl_dbw0_1234.trc"));
+
+    assert!(name_matcher("re:(").is_err());
+}
+
+#[test]
+#[cfg(any(feature = "agent", feature = "remote"))]
+fn test_hash_reader() {
+    let content: &[u8] = b"Hello world";
+    assert_eq!(
+        ("3e25960a79dbc69b674cd4ec67a72c62".to_owned(), 11),
+        hash_reader(content, "md5").unwrap()
+    );
+    assert_eq!(
+        "7b502c3a1f48c8609ae212cdfb639dee39673f5e",
+        hash_reader(content, "SHA1").unwrap().0
+    );
+    assert_eq!(
+        "64ec88ca00b268e5ba1a35678a1b5316d212f4f366b2477232534a8aeca37f3c",
+        hash_reader(content, "sha256").unwrap().0
+    );
+    assert_eq!("8bd69e52", hash_reader(content, "crc32").unwrap().0);
+    assert!(hash_reader(content, "sha512").is_err());
+}
+
+#[test]
+#[cfg(any(feature = "agent", feature = "remote"))]
+fn test_tail_offset() {
+    assert_eq!((10, false), tail_offset(10, 100, 1, None));
+    assert_eq!((10, false), tail_offset(10, 100, 1, Some(1)));
+    // 文件被轮转
+    assert_eq!((0, false), tail_offset(10, 100, 2, Some(1)));
+    // 文件被截断
+    assert_eq!((0, false), tail_offset(200, 100, 1, None));
+    assert_eq!((89, true), tail_offset(-10, 100, 1, None));
+    assert_eq!((0, false), tail_offset(-200, 100, 1, None));
+}
+
+#[test]
+#[cfg(any(feature = "agent", feature = "remote"))]
+fn test_commit_lines() {
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    std::thread::spawn(move || {
+        let mut promise = req.head::<FileLine>().unwrap();
+        let content: &[u8] = b"ial\nfirst\r\nsecond\nthird\npart";
+        if let Err(err) = commit_lines(content, 10, true, 7, Some(2), &mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &crate::columns![String: "line", Integer: "line_num", Integer: "byte_offset", Integer: "inode"],
+        resp.columns()
+    );
+
+    let mut lines = vec![];
+    for row in resp {
+        let row = row.unwrap();
+        let line: String = row.get(0).unwrap();
+        let byte_offset: i64 = row.get(2).unwrap();
+        let inode: i64 = row.get(3).unwrap();
+        assert_eq!(7, inode);
+        lines.push((line, byte_offset));
+    }
+    assert_eq!(
+        vec![("first".to_owned(), 21), ("second".to_owned(), 28)],
+        lines
+    );
+}
@@ -0,0 +1,97 @@
+use super::{run_sections, SessionPool};
+use crate::{datasource::CPUUsage, Promise, Result, ToData};
+use std::sync::Arc;
+
+#[datasource]
+pub fn cpu_usage(pool: Arc<SessionPool>, promise: &mut Promise<CPUUsage>) -> Result<()> {
+    let session = pool.get()?;
+    // 间隔 1s 采样两次 CPU 时间
+    let sections = run_sections(
+        &session,
+        &["head -n 1 /proc/stat", "sleep 1; head -n 1 /proc/stat"],
+    )?;
+    let before = parse_stat(&sections[0]);
+    let after = parse_stat(&sections[1]);
+
+    promise.commit(usage(&before, &after))?;
+    Ok(())
+}
+
+/// 解析 `/proc/stat` 中 cpu 汇总行的各项时间:
+/// user nice system idle iowait irq softirq steal
+fn parse_stat(content: &str) -> Vec<f64> {
+    let mut times: Vec<f64> = content
+        .split_whitespace()
+        .skip(1)
+        .take(8)
+        .map(|val| val.parse().unwrap_or(0.0))
+        .collect();
+    times.resize(8, 0.0);
+    times
+}
+
+fn usage(before: &[f64], after: &[f64]) -> CPUUsage {
+    let delta: Vec<f64> = after
+        .iter()
+        .zip(before.iter())
+        .map(|(now, prev)| (now - prev).max(0.0))
+        .collect();
+    let total: f64 = delta.iter().sum();
+    let ratio = |index: usize| {
+        if total > 0.0 {
+            (delta[index] * 100.0 / total).min(100.0)
+        } else {
+            0.0
+        }
+    };
+
+    CPUUsage {
+        idle: ratio(3),
+        user: ratio(0),
+        system: ratio(2),
+        iowait: ratio(4),
+    }
+}
+
+#[test]
+fn test_usage() {
+    let before = parse_stat("cpu  100 0 50 800 50 0 0 0 0 0\n");
+    let after = parse_stat("cpu  200 0 100 1600 100 0 0 0 0 0\n");
+    assert!(
+        CPUUsage {
+            idle: 80.0,
+            user: 10.0,
+            system: 5.0,
+            iowait: 5.0,
+        } == usage(&before, &after)
+    );
+    assert!(usage(&before, &before).idle == 0.0);
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    let (pool, _) = super::new_test_sess().unwrap();
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(4));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<CPUUsage>().unwrap();
+        if let Err(err) = cpu_usage(pool, &mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![Number: "idle",Number: "user", Number: "system", Number: "iowait"],
+        resp.columns()
+    );
+
+    let mut index = 0;
+    for row in resp {
+        let _ = row.unwrap();
+        index += 1;
+    }
+    assert_eq!(1, index);
+}
@@ -0,0 +1,107 @@
+use super::{local_path, SessionPool};
+use crate::{datasource::FileTransfer, Instance, Promise, Result, ToData, ToType};
+use ssh::Session;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// 临时文件的序号，避免同时下载到同一文件时互相覆盖
+static TEMP_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+#[datasource]
+pub fn download(
+    instance: Arc<Instance>,
+    pool: Arc<SessionPool>,
+    remote: String,
+    local: String,
+    promise: &mut Promise<FileTransfer>,
+) -> Result<()> {
+    let session = pool.get()?;
+    let target = local_path(&instance, &local)?;
+    // 先写入同一目录中的临时文件，下载完成后再替换目标文件，避免下载失败时留下不完整的文件
+    let name = target.file_name().unwrap_or_default().to_string_lossy();
+    let temp = target.with_file_name(format!(
+        ".{}.{}.{}.download",
+        name,
+        std::process::id(),
+        TEMP_SEQ.fetch_add(1, Ordering::Relaxed)
+    ));
+    let size = match session.check(download_file(&session, &remote, &temp)) {
+        Ok(size) => size,
+        Err(err) => {
+            let _ = std::fs::remove_file(&temp);
+            return Err(err);
+        }
+    };
+    if let Err(err) = std::fs::rename(&temp, &target) {
+        let _ = std::fs::remove_file(&temp);
+        return Err(err.into());
+    }
+    info!(
+        "download file - {} to {} with size = {}",
+        remote, local, size
+    );
+
+    promise.commit(FileTransfer {
+        source: remote,
+        target: local,
+        size: size as i64,
+    })?;
+    Ok(())
+}
+
+/// 通过 sftp 下载远程文件，返回下载的字节数
+fn download_file(session: &Session, remote: &str, local: &Path) -> Result<u64> {
+    let mut sftp = session.sftp_new()?;
+    sftp.init()?;
+    let file_path: PathBuf = remote.parse()?;
+    let mut source = sftp.open(&file_path, libc::O_RDONLY as usize, 0)?;
+
+    let mut target = std::fs::File::create(local)?;
+    let size = std::io::copy(&mut source, &mut target)?;
+    target.sync_all()?;
+    Ok(size)
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    const REMOTE: &str = "/etc/hosts";
+    const LOCAL: &str = "/tmp/bee_download_hosts";
+    let (pool, instance) = super::new_test_sess().unwrap();
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<FileTransfer>().unwrap();
+        if let Err(err) = download(
+            instance,
+            pool,
+            REMOTE.to_owned(),
+            LOCAL.to_owned(),
+            &mut promise,
+        ) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "source", String: "target", Integer: "size"],
+        resp.columns()
+    );
+
+    let mut index = 0;
+    for row in resp {
+        let row = row.unwrap();
+        let size: i64 = row.get(2).unwrap();
+        assert_eq!(std::fs::metadata(REMOTE).unwrap().len() as i64, size);
+        index += 1;
+    }
+    assert_eq!(1, index);
+    assert_eq!(
+        std::fs::read(REMOTE).unwrap(),
+        std::fs::read(LOCAL).unwrap()
+    );
+    std::fs::remove_file(LOCAL).unwrap();
+}
@@ -0,0 +1,68 @@
+use super::SessionPool;
+use crate::datasource::{hash_reader, FileHash};
+use crate::{Promise, Result, ToData, ToType};
+use ssh::Session;
+use std::{path::PathBuf, sync::Arc};
+
+#[datasource]
+pub fn file_hash(
+    pool: Arc<SessionPool>,
+    path: String,
+    algorithm: String,
+    promise: &mut Promise<FileHash>,
+) -> Result<()> {
+    let session = pool.get()?;
+    let (hash, size) = session.check(hash_file(&session, &path, &algorithm))?;
+    promise.commit(FileHash {
+        path,
+        algorithm: algorithm.to_lowercase(),
+        hash,
+        size: size as i64,
+    })?;
+    Ok(())
+}
+
+/// 通过 sftp 流式读取文件内容，在本地计算摘要
+fn hash_file(session: &Session, path: &str, algorithm: &str) -> Result<(String, u64)> {
+    let mut sftp = session.sftp_new()?;
+    sftp.init()?;
+
+    let file_path: PathBuf = path.parse()?;
+    let file = sftp.open(&file_path, libc::O_RDONLY as usize, 0)?;
+    hash_reader(file, algorithm)
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    let (pool, _) = super::new_test_sess().unwrap();
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<FileHash>().unwrap();
+        if let Err(err) = file_hash(
+            pool,
+            "/etc/hosts".to_owned(),
+            "sha256".to_owned(),
+            &mut promise,
+        ) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "path", String: "algorithm", String: "hash", Integer: "size"],
+        resp.columns()
+    );
+
+    let mut index = 0;
+    for row in resp {
+        let row = row.unwrap();
+        let hash: String = row.get(2).unwrap();
+        assert_eq!(64, hash.len());
+        index += 1;
+    }
+    assert_eq!(1, index);
+}
@@ -0,0 +1,159 @@
+use super::{run_sections, SessionPool};
+use crate::datasource::{unescape, Filesystem};
+use crate::{Promise, Result, ToData, ToType};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// df 输出中的容量以及 inode 统计
+#[derive(Default)]
+struct Usage {
+    total: u64,
+    used: u64,
+    free: u64,
+}
+
+#[datasource]
+pub fn filesystem(
+    pool: Arc<SessionPool>,
+    all: Option<bool>,
+    promise: &mut Promise<Filesystem>,
+) -> Result<()> {
+    let session = pool.get()?;
+    let sections = run_sections(
+        &session,
+        &[
+            "cat /proc/mounts",
+            "cat /proc/filesystems",
+            "df -P -k",
+            "df -P -i",
+        ],
+    )?;
+    for filesystem in parse_filesystems(&sections, all.unwrap_or(false)) {
+        promise.commit(filesystem)?;
+    }
+    Ok(())
+}
+
+/// 根据 `/proc/mounts`、`/proc/filesystems` 以及 df 的输出生成文件系统列表
+///
+/// 默认只返回物理磁盘分区 (`/proc/filesystems` 中不带 nodev 标记的文件系统类型)，`all` 为 true 时包含所有挂载点
+fn parse_filesystems(sections: &[String], all: bool) -> Vec<Filesystem> {
+    let physical: HashSet<&str> = sections[1]
+        .lines()
+        .filter(|line| !line.starts_with("nodev"))
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+    let blocks = parse_df(&sections[2]);
+    let inodes = parse_df(&sections[3]);
+
+    let empty = Usage::default();
+    let mut mounted = HashSet::new();
+    let mut rs = vec![];
+    for line in sections[0].lines() {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        if cols.len() < 4 || (!all && !physical.contains(cols[2])) {
+            continue;
+        }
+        let mount_on = unescape(cols[1]);
+        let usage = match blocks.get(&mount_on) {
+            Some(usage) if usage.total > 0 => usage,
+            _ => continue,
+        };
+        if !mounted.insert(mount_on.clone()) {
+            continue;
+        }
+        let inode = inodes.get(&mount_on).unwrap_or(&empty);
+        let options = cols[3].to_owned();
+        rs.push(Filesystem {
+            name: unescape(cols[0]),
+            mount_on,
+            total_bytes: (usage.total * 1024) as i64,
+            used_bytes: (usage.used * 1024) as i64,
+            free_bytes: (usage.free * 1024) as i64,
+            fs_type: cols[2].to_owned(),
+            read_only: options.split(',').any(|opt| opt == "ro"),
+            options,
+            inodes_total: inode.total as i64,
+            inodes_used: inode.total.saturating_sub(inode.free) as i64,
+            inodes_free: inode.free as i64,
+        });
+    }
+    rs
+}
+
+/// 解析 `df -P` 的输出，按照挂载点返回使用量
+fn parse_df(content: &str) -> HashMap<String, Usage> {
+    let mut rs = HashMap::new();
+    for line in content.lines().skip(1) {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        if cols.len() < 6 {
+            continue;
+        }
+        let value = |index: usize| cols[index].parse::<u64>().unwrap_or(0);
+        rs.insert(
+            cols[5..].join(" "),
+            Usage {
+                total: value(1),
+                used: value(2),
+                free: value(3),
+            },
+        );
+    }
+    rs
+}
+
+#[test]
+fn test_parse() {
+    let sections: Vec<String> = vec![
+        "/dev/sda1 / ext4 rw,relatime 0 0\ntmpfs /dev/shm tmpfs rw 0 0\n/dev/sdb1 /mnt/my\\040disk xfs ro,noatime 0 0\n",
+        "nodev\tsysfs\nnodev\ttmpfs\n\text4\n\txfs\n",
+        "Filesystem     1024-blocks    Used Available Capacity Mounted on\n/dev/sda1         15312232 9295008   5219684      65% /\ntmpfs              1018900       0   1018900       0% /dev/shm\n/dev/sdb1             1000     400       600      40% /mnt/my disk\n",
+        "Filesystem      Inodes  IUsed   IFree IUse% Mounted on\n/dev/sda1       983040 200000  783040   21% /\n",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+
+    let rs = parse_filesystems(&sections, false);
+    assert_eq!(2, rs.len());
+    assert_eq!("/", rs[0].mount_on);
+    assert_eq!(15312232 * 1024, rs[0].total_bytes);
+    assert_eq!(983040, rs[0].inodes_total);
+    assert_eq!(200000, rs[0].inodes_used);
+    assert!(!rs[0].read_only);
+    assert_eq!("/mnt/my disk", rs[1].mount_on);
+    assert_eq!("xfs", rs[1].fs_type);
+    assert_eq!(0, rs[1].inodes_total);
+    assert!(rs[1].read_only);
+
+    assert_eq!(3, parse_filesystems(&sections, true).len());
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    let (pool, _) = super::new_test_sess().unwrap();
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<Filesystem>().unwrap();
+        if let Err(err) = filesystem(pool, Some(true), &mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "name", String: "mount_on", Integer: "total_bytes",Integer: "used_bytes", Integer: "free_bytes", String: "fs_type", String: "options", Integer: "inodes_total", Integer: "inodes_used", Integer: "inodes_free", Boolean: "read_only"],
+        resp.columns()
+    );
+
+    let mut index = 0;
+    for row in resp {
+        let _ = row.unwrap();
+        index += 1;
+    }
+    assert!(index > 0);
+}
@@ -0,0 +1,201 @@
+use super::{exec, quote, SessionPool, Stream, COMMAND_TIMEOUT, EXIT_STATUS};
+use crate::datasource::{name_matcher, FoundFile};
+use crate::{Error, Promise, Result, ToData, ToType};
+use regex::Regex;
+use std::sync::Arc;
+
+/// 每个文件输出的字段数量，字段之间使用 `\0` 分隔
+const FIELDS: usize = 4;
+
+/// 查找远程主机上的文件，需要远程主机支持 GNU find 的 `-printf` 参数
+///
+/// 通配符模式通过 `find -name` 在远程主机上过滤，正则表达式在本地过滤。
+/// 文件按照 find 遍历的顺序边读取边提交，达到 `max_files` 后立即终止远程命令。
+/// 返回的顺序不做保证，与本地模式按照文件名排序的顺序可能不同
+#[datasource]
+pub fn find_files(
+    pool: Arc<SessionPool>,
+    root: String,
+    pattern: String,
+    max_depth: Option<u32>,
+    exclude_hidden: Option<bool>,
+    max_files: Option<u32>,
+    promise: &mut Promise<FoundFile>,
+) -> Result<()> {
+    let session = pool.get()?;
+    let matcher = name_matcher(&pattern)?;
+    let cmd = find_command(&root, &pattern, max_depth, exclude_hidden.unwrap_or(false));
+    info!("find files - {}", cmd);
+
+    let max_files = max_files.filter(|max| *max > 0).unwrap_or(u32::MAX) as usize;
+    let mut decoder = FileDecoder::new(matcher);
+    let mut count = 0;
+    let mut stderr = vec![];
+    let result = exec(&session, &cmd, COMMAND_TIMEOUT, true, |stream, data| {
+        if promise.is_cancelled() {
+            return Err(Error::cancelled("the remote command is cancelled"));
+        }
+        match stream {
+            Stream::Stdout => {
+                for file in decoder.decode(data) {
+                    promise.commit(file)?;
+                    count += 1;
+                    if count >= max_files {
+                        // 已经达到最多返回的文件数量，终止远程命令
+                        return Err(Error::cancelled("reach the max files"));
+                    }
+                }
+            }
+            Stream::Stderr => stderr.extend_from_slice(data),
+        }
+        Ok(())
+    });
+
+    match result {
+        Ok(0) => Ok(()),
+        Ok(status) => Err(Error::other(
+            EXIT_STATUS,
+            format!(
+                "cmd - [{}] exit with code {} - {}",
+                cmd,
+                status,
+                String::from_utf8_lossy(&stderr).trim()
+            ),
+        )),
+        Err(_) if count >= max_files => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+/// 生成查找文件的命令，通配符模式转换为 `find -name` 的参数
+fn find_command(root: &str, pattern: &str, max_depth: Option<u32>, exclude_hidden: bool) -> String {
+    let max_depth = max_depth
+        .filter(|depth| *depth > 0)
+        .map(|depth| format!(" -maxdepth {}", depth))
+        .unwrap_or_default();
+    let prune = if exclude_hidden {
+        " -name '.*' -prune -o"
+    } else {
+        ""
+    };
+    // find 中的 `\` 为转义字符，通配符模式中按照普通字符匹配
+    let name = if pattern.starts_with("re:") {
+        String::new()
+    } else {
+        format!(" -name {}", quote(&pattern.replace('\\', "\\\\")))
+    };
+    let root = quote(root);
+    format!(
+        "test -d {root} && {{ find {root} -mindepth 1{depth}{prune} -type f{name} -printf '%p\\0%f\\0%s\\0%T@\\0' 2>/dev/null; true; }}",
+        root = root,
+        depth = max_depth,
+        prune = prune,
+        name = name,
+    )
+}
+
+/// 解析 find 的输出，数据可能在任意位置被截断，未读取完整的字段会保留到下一次解析
+struct FileDecoder {
+    matcher: Regex,
+    // 尚未读取到分隔符的数据
+    pending: Vec<u8>,
+    // 当前文件已经读取的字段
+    fields: Vec<String>,
+}
+
+impl FileDecoder {
+    fn new(matcher: Regex) -> Self {
+        Self {
+            matcher,
+            pending: vec![],
+            fields: vec![],
+        }
+    }
+
+    /// 解析读取到的数据，返回已经读取完整并且文件名匹配的文件
+    fn decode(&mut self, data: &[u8]) -> Vec<FoundFile> {
+        let mut files = vec![];
+        self.pending.extend_from_slice(data);
+        while let Some(index) = self.pending.iter().position(|ch| *ch == 0) {
+            let field: Vec<u8> = self.pending.drain(..=index).collect();
+            self.fields
+                .push(String::from_utf8_lossy(&field[..index]).to_string());
+            if self.fields.len() < FIELDS {
+                continue;
+            }
+            let fields = std::mem::take(&mut self.fields);
+            // 正则表达式只能在本地过滤
+            if self.matcher.is_match(&fields[1]) {
+                files.push(FoundFile {
+                    path: fields[0].to_owned(),
+                    name: fields[1].to_owned(),
+                    size: fields[2].parse().unwrap_or(0),
+                    mtime: fields[3].parse::<f64>().map(|t| t as i64).unwrap_or(0),
+                });
+            }
+        }
+        files
+    }
+}
+
+#[test]
+fn test_decode_output() {
+    let output = "/u01/trace/alert_orcl2.log\0alert_orcl2.log\011\01600000000.5\0\
+        /u01/trace/orcl_ora_1.trc\0orcl_ora_1.trc\05\01600000000.5\0\
+        /u01/alert_orcl.log\0alert_orcl.log\05\01600000001.5\0";
+    let mut decoder = FileDecoder::new(name_matcher("alert_*.log").unwrap());
+    // 数据在字段中间被截断
+    let (head, tail) = output.as_bytes().split_at(40);
+    let mut files = decoder.decode(head);
+    assert!(files.is_empty());
+    files.extend(decoder.decode(tail));
+    let names: Vec<&str> = files.iter().map(|file| file.name.as_str()).collect();
+    assert_eq!(vec!["alert_orcl2.log", "alert_orcl.log"], names);
+    assert_eq!(11, files[0].size);
+    assert_eq!(1600000001, files[1].mtime);
+}
+
+#[test]
+fn test_find_command() {
+    let cmd = find_command("/u01", "alert_*.log", Some(2), true);
+    assert!(cmd.contains(" -maxdepth 2 -name '.*' -prune -o -type f -name 'alert_*.log' -printf"));
+    let cmd = find_command("/u01", "re:^alert_.*$", None, false);
+    assert!(cmd.contains("-mindepth 1 -type f -printf"));
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    let (pool, _) = super::new_test_sess().unwrap();
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<FoundFile>().unwrap();
+        if let Err(err) = find_files(
+            pool,
+            "/etc".to_owned(),
+            "host*".to_owned(),
+            Some(1),
+            None,
+            None,
+            &mut promise,
+        ) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "path", String: "name", Integer: "size", Integer: "mtime"],
+        resp.columns()
+    );
+
+    let mut names = vec![];
+    for row in resp {
+        let row = row.unwrap();
+        let name: String = row.get(1).unwrap();
+        names.push(name);
+    }
+    assert!(names.contains(&"hosts".to_owned()));
+}
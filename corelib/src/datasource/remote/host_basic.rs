@@ -0,0 +1,100 @@
+use super::{parse_meminfo, run_sections, SessionPool};
+use crate::{datasource::HostBasic, Promise, Result, ToData};
+use std::sync::Arc;
+
+#[datasource]
+pub fn host_basic(pool: Arc<SessionPool>, promise: &mut Promise<HostBasic>) -> Result<()> {
+    let session = pool.get()?;
+    let sections = run_sections(
+        &session,
+        &[
+            "uname -n",
+            "cat /proc/cpuinfo",
+            "cat /proc/uptime",
+            "cat /proc/meminfo",
+        ],
+    )?;
+    promise.commit(parse_host_basic(&sections))?;
+    Ok(())
+}
+
+fn parse_host_basic(sections: &[String]) -> HostBasic {
+    let mut cpu_core = 0;
+    let mut cpu_model = String::new();
+    for line in sections[1].lines() {
+        let mut cols = line.splitn(2, ':');
+        match (cols.next().map(|key| key.trim()), cols.next()) {
+            (Some("processor"), _) => cpu_core += 1,
+            (Some("model name"), Some(model)) if cpu_model.is_empty() => {
+                cpu_model = model.trim().to_owned()
+            }
+            _ => {}
+        }
+    }
+    let uptime = sections[2]
+        .split_whitespace()
+        .next()
+        .and_then(|val| val.parse::<f64>().ok())
+        .unwrap_or(0.0);
+    let memory = parse_meminfo(&sections[3])
+        .get("MemTotal")
+        .cloned()
+        .unwrap_or(0);
+
+    HostBasic {
+        host_name: sections[0].trim().to_owned(),
+        cpu_core: cpu_core.max(1),
+        cpu_model,
+        uptime: uptime as i64,
+        memory: memory as i64,
+    }
+}
+
+#[test]
+fn test_parse() {
+    let sections: Vec<String> = vec![
+        "db01\n",
+        "processor\t: 0\nmodel name\t: Intel(R) Xeon(R) CPU\n\nprocessor\t: 1\nmodel name\t: Intel(R) Xeon(R) CPU\n",
+        "350735.47 1375324.61\n",
+        "MemTotal:        2037800 kB\n",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+    let host = parse_host_basic(&sections);
+    assert_eq!("db01", host.host_name);
+    assert_eq!(2, host.cpu_core);
+    assert_eq!("Intel(R) Xeon(R) CPU", host.cpu_model);
+    assert_eq!(350735, host.uptime);
+    assert_eq!(2037800 * 1024, host.memory);
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    let (pool, _) = super::new_test_sess().unwrap();
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<HostBasic>().unwrap();
+        if let Err(err) = host_basic(pool, &mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "host_name", Integer: "cpu_core", String: "cpu_model",Integer: "uptime", Integer: "memory"],
+        resp.columns()
+    );
+
+    let mut index = 0;
+    for row in resp {
+        let row = row.unwrap();
+        let memory: i64 = row.get(4).unwrap();
+        assert!(memory > 0);
+        index += 1;
+    }
+    assert_eq!(1, index);
+}
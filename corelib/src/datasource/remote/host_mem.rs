@@ -0,0 +1,55 @@
+use super::{parse_meminfo, run_sections, SessionPool};
+use crate::{datasource::MemoryUsage, Promise, Result, ToData};
+use std::sync::Arc;
+
+#[datasource]
+pub fn memory_usage(pool: Arc<SessionPool>, promise: &mut Promise<MemoryUsage>) -> Result<()> {
+    let session = pool.get()?;
+    let sections = run_sections(&session, &["cat /proc/meminfo"])?;
+    let info = parse_meminfo(&sections[0]);
+    let get = |key: &str| info.get(key).cloned().unwrap_or(0);
+
+    let total = get("MemTotal");
+    // 3.14 之前的内核没有 MemAvailable
+    let available = info
+        .get("MemAvailable")
+        .cloned()
+        .unwrap_or_else(|| get("MemFree") + get("Buffers") + get("Cached"))
+        .min(total);
+    promise.commit(MemoryUsage {
+        used_bytes: (total - available) as i64,
+        total_bytes: total as i64,
+        free_bytes: available as i64,
+    })?;
+    Ok(())
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    let (pool, _) = super::new_test_sess().unwrap();
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<MemoryUsage>().unwrap();
+        if let Err(err) = memory_usage(pool, &mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![Integer: "used_bytes", Integer: "total_bytes", Integer: "free_bytes"],
+        resp.columns()
+    );
+
+    let mut index = 0;
+    for row in resp {
+        let row = row.unwrap();
+        let total: i64 = row.get(1).unwrap();
+        assert!(total > 0);
+        index += 1;
+    }
+    assert_eq!(1, index);
+}
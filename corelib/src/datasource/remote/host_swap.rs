@@ -0,0 +1,47 @@
+use super::{parse_meminfo, run_sections, SessionPool};
+use crate::{datasource::SWAPUsage, Promise, Result, ToData};
+use std::sync::Arc;
+
+#[datasource]
+pub fn swap_usage(pool: Arc<SessionPool>, promise: &mut Promise<SWAPUsage>) -> Result<()> {
+    let session = pool.get()?;
+    let sections = run_sections(&session, &["cat /proc/meminfo"])?;
+    let info = parse_meminfo(&sections[0]);
+    let total = info.get("SwapTotal").cloned().unwrap_or(0);
+    let free = info.get("SwapFree").cloned().unwrap_or(0).min(total);
+
+    promise.commit(SWAPUsage {
+        used_bytes: (total - free) as i64,
+        total_bytes: total as i64,
+        free_bytes: free as i64,
+    })?;
+    Ok(())
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    let (pool, _) = super::new_test_sess().unwrap();
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<SWAPUsage>().unwrap();
+        if let Err(err) = swap_usage(pool, &mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![Integer: "used_bytes", Integer: "total_bytes", Integer: "free_bytes"],
+        resp.columns()
+    );
+
+    let mut index = 0;
+    for row in resp {
+        let _ = row.unwrap();
+        index += 1;
+    }
+    assert_eq!(1, index);
+}
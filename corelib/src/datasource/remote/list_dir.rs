@@ -0,0 +1,128 @@
+use super::{quote, run_command, SessionPool, COMMAND_TIMEOUT};
+use crate::{datasource::FileEntry, Promise, Result, ToData, ToType};
+use std::sync::Arc;
+
+/// 每个文件输出的字段数量，字段之间使用 `\0` 分隔
+const FIELDS: usize = 11;
+
+#[datasource]
+pub fn list_dir(
+    pool: Arc<SessionPool>,
+    path: String,
+    recursive: Option<bool>,
+    max_depth: Option<u32>,
+    promise: &mut Promise<FileEntry>,
+) -> Result<()> {
+    let session = pool.get()?;
+    // 非递归时只列出当前目录，递归时未指定深度则不限制
+    let max_depth = if recursive.unwrap_or(false) {
+        max_depth
+            .filter(|depth| *depth > 0)
+            .map(|depth| format!(" -maxdepth {}", depth))
+            .unwrap_or_default()
+    } else {
+        " -maxdepth 1".to_owned()
+    };
+    let path = quote(&path);
+    // 无权限访问的子目录会被忽略，只有目录本身无法访问时才返回错误
+    let cmd = format!(
+        "test -d {path} && {{ find {path} -mindepth 1{depth} -printf '{format}' 2>/dev/null; true; }}",
+        path = path,
+        depth = max_depth,
+        format = "%f\\0%p\\0%y\\0%s\\0%m\\0%u\\0%g\\0%T@\\0%A@\\0%C@\\0%l\\0",
+    );
+    info!("list dir - {}", cmd);
+    let output = run_command(&session, &cmd, COMMAND_TIMEOUT)?;
+
+    for entry in decode_output(&String::from_utf8_lossy(&output)) {
+        promise.commit(entry)?;
+    }
+    Ok(())
+}
+
+/// 解析 find 的输出，按照与 agent 模式一致的目录深度优先顺序排序
+fn decode_output(output: &str) -> Vec<FileEntry> {
+    let fields: Vec<&str> = output.split('\0').collect();
+    let mut entries: Vec<FileEntry> = fields
+        .chunks_exact(FIELDS)
+        .map(|fields| FileEntry {
+            name: fields[0].to_owned(),
+            path: fields[1].to_owned(),
+            file_type: file_type(fields[2]).to_owned(),
+            size: fields[3].parse().unwrap_or(0),
+            mode: fields[4].to_owned(),
+            owner: fields[5].to_owned(),
+            group: fields[6].to_owned(),
+            mtime: timestamp(fields[7]),
+            atime: timestamp(fields[8]),
+            ctime: timestamp(fields[9]),
+            link_target: fields[10].to_owned(),
+        })
+        .collect();
+    entries.sort_by(|a, b| a.path.split('/').cmp(b.path.split('/')));
+    entries
+}
+
+fn file_type(value: &str) -> &'static str {
+    match value {
+        "f" => "file",
+        "d" => "dir",
+        "l" => "symlink",
+        "b" => "block",
+        "c" => "char",
+        "p" => "fifo",
+        "s" => "socket",
+        _ => "other",
+    }
+}
+
+/// find 输出的时间格式为 `1600000000.1234567890`
+fn timestamp(value: &str) -> i64 {
+    value.parse::<f64>().map(|t| t as i64).unwrap_or(0)
+}
+
+#[test]
+fn test_decode_output() {
+    let output = "sub\0/tmp/t/sub\0d\04096\0755\0root\0root\01600000000.5\01600000001.0\01600000002.0\0\0\
+        b.log\0/tmp/t/sub/b.log\0f\011\0644\0root\0root\01600000000.5\01600000001.0\01600000002.0\0\0\
+        a.log\0/tmp/t/a.log\0l\05\0777\0root\0root\01600000000.5\01600000001.0\01600000002.0\0sub/b.log\0";
+    let entries = decode_output(output);
+    let names: Vec<&str> = entries.iter().map(|entry| entry.name.as_str()).collect();
+    assert_eq!(vec!["a.log", "sub", "b.log"], names);
+    assert_eq!("symlink", entries[0].file_type);
+    assert_eq!("sub/b.log", entries[0].link_target);
+    assert_eq!(1600000000, entries[0].mtime);
+    assert_eq!(11, entries[2].size);
+    assert_eq!("644", entries[2].mode);
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    let (pool, _) = super::new_test_sess().unwrap();
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<FileEntry>().unwrap();
+        if let Err(err) = list_dir(pool, "/etc".to_owned(), None, None, &mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "name", String: "path", String: "file_type", Integer: "size", String: "mode", String: "owner", String: "group", Integer: "mtime", Integer: "atime", Integer: "ctime", String: "link_target"],
+        resp.columns()
+    );
+
+    let mut has_hosts = false;
+    for row in resp {
+        let row = row.unwrap();
+        let path: String = row.get(1).unwrap();
+        if path == "/etc/hosts" {
+            has_hosts = true;
+        }
+    }
+    assert!(has_hosts);
+}
@@ -1,35 +1,38 @@
+use super::SessionPool;
 use crate::{datasource::Status, Promise, Result, ToData, ToType};
 use ssh::Session;
 use std::sync::Arc;
 
 #[datasource]
 pub fn mkdir(
-    session: Arc<Session>,
+    pool: Arc<SessionPool>,
     home_dir: String,
     dir: String,
     promise: &mut Promise<Status>,
 ) -> Result<()> {
-    let mut scp = session.scp_new(ssh::Mode::RECURSIVE | ssh::Mode::WRITE, &home_dir)?;
-    scp.init()?;
-    scp.push_directory(&dir, 0o755)?;
+    let session = pool.get()?;
+    session.check(push_directory(&session, &home_dir, &dir))?;
 
     promise.commit(Status { success: true })?;
     Ok(())
 }
 
+/// 通过 scp 创建目录
+fn push_directory(session: &Session, home_dir: &str, dir: &str) -> Result<()> {
+    let mut scp = session.scp_new(ssh::Mode::RECURSIVE | ssh::Mode::WRITE, home_dir)?;
+    scp.init()?;
+    scp.push_directory(dir, 0o755)?;
+    Ok(())
+}
+
 #[test]
 fn test() {
     use crate::*;
-    let (session, _) = super::new_test_sess().unwrap();
+    let (pool, _) = super::new_test_sess().unwrap();
     let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
     async_std::task::spawn_blocking(move || {
         let mut promise = req.head::<Status>().unwrap();
-        if let Err(err) = mkdir(
-            session,
-            "/tmp".to_owned(),
-            "bethune".to_owned(),
-            &mut promise,
-        ) {
+        if let Err(err) = mkdir(pool, "/tmp".to_owned(), "bethune".to_owned(), &mut promise) {
             let _ = req.error(err);
         } else {
             let _ = req.ok();
@@ -0,0 +1,46 @@
+use super::{run_sections, SessionPool};
+use crate::{datasource::OSInfo, Promise, Result, ToData};
+use std::sync::Arc;
+
+#[datasource]
+pub fn os_info(pool: Arc<SessionPool>, promise: &mut Promise<OSInfo>) -> Result<()> {
+    let session = pool.get()?;
+    let sections = run_sections(&session, &["uname -s", "uname -r", "uname -n"])?;
+
+    promise.commit(OSInfo {
+        os_type: sections[0].trim().to_owned(),
+        version: sections[1].trim().to_owned(),
+        host_name: sections[2].trim().to_owned(),
+    })?;
+    Ok(())
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    let (pool, _) = super::new_test_sess().unwrap();
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<OSInfo>().unwrap();
+        if let Err(err) = os_info(pool, &mut promise) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "os_type",String: "version",String: "host_name"],
+        resp.columns()
+    );
+
+    let mut index = 0;
+    for row in resp {
+        let row = row.unwrap();
+        let os_type: String = row.get(0).unwrap();
+        assert_eq!("Linux", os_type);
+        index += 1;
+    }
+    assert_eq!(1, index);
+}
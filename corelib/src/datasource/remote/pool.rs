@@ -0,0 +1,233 @@
+use super::{is_connection_error, new_session};
+use crate::{Error, Instance, Result};
+use parking_lot::{Condvar, Mutex};
+use ssh::Session;
+use std::cell::Cell;
+use std::ops::Deref;
+use std::time::{Duration, Instant};
+
+/// 每个会话池默认的最大会话数量
+const DEFAULT_MAX_SESSIONS: u32 = 4;
+
+/// 会话空闲超过该时间后，再次取出时需要检查连接是否可用
+const IDLE_CHECK: Duration = Duration::from_secs(30);
+
+/// SSH 会话池
+///
+/// libssh 的会话不支持多个线程同时使用，每个数据源调用会独占一个会话，
+/// 会话数量达到 `max_sessions` 后，新的调用需要等待其他调用归还会话。
+/// 会话池属于创建它的连接，多个连接访问同一主机时各自计算会话数量。
+///
+/// 空闲超过 `IDLE_CHECK` 的会话在取出时会检查其是否可用，连接断开的会话会被丢弃并重新建立连接。
+/// 最近使用过的会话不再检查，避免每次调用都增加一次打开通道的往返；
+/// 使用会话时返回了连接错误的会话 (参考 [`PooledSession::check`]) 不会归还到会话池，下次调用会重新建立连接。
+pub struct SessionPool {
+    instance: Instance,
+    max_sessions: usize,
+    wait_timeout: Duration,
+    state: Mutex<PoolState>,
+    available: Condvar,
+}
+
+struct PoolState {
+    // 空闲的会话以及归还的时间
+    idle: Vec<(Session, Instant)>,
+    // 已经创建的会话数量，包括正在使用的会话
+    size: usize,
+}
+
+/// 从会话池中取出的会话，释放时归还到会话池
+pub struct PooledSession<'a> {
+    pool: &'a SessionPool,
+    session: Option<Session>,
+    // 使用过程中出现连接错误，释放时丢弃该会话
+    broken: Cell<bool>,
+}
+
+impl SessionPool {
+    /// 创建会话池，并建立第一个会话以便尽早发现连接或者认证的错误
+    pub fn new(instance: &Instance) -> Result<Self> {
+        let max_sessions: u32 = instance
+            .get_param("max_sessions")
+            .unwrap_or(DEFAULT_MAX_SESSIONS);
+        let connect_timeout: u32 = instance.get_param("connect_timeout").unwrap_or(5);
+        let session = new_session(instance)?;
+
+        Ok(Self {
+            instance: instance.clone(),
+            max_sessions: max_sessions.max(1) as usize,
+            wait_timeout: Duration::from_secs(connect_timeout as u64),
+            state: Mutex::new(PoolState {
+                idle: vec![(session, Instant::now())],
+                size: 1,
+            }),
+            available: Condvar::new(),
+        })
+    }
+
+    /// 获取一个可用的会话，会话数量达到上限时等待其他调用归还
+    pub fn get(&self) -> Result<PooledSession> {
+        let mut state = self.state.lock();
+        loop {
+            if let Some((session, released)) = state.idle.pop() {
+                if released.elapsed() < IDLE_CHECK {
+                    return Ok(self.pooled(session));
+                }
+                // 检查会话时不需要持有锁
+                let session = parking_lot::MutexGuard::unlocked(&mut state, || {
+                    if is_alive(&session) {
+                        Some(session)
+                    } else {
+                        warn!("ssh session is disconnected, reconnecting");
+                        None
+                    }
+                });
+                match session {
+                    Some(session) => return Ok(self.pooled(session)),
+                    None => {
+                        state.size -= 1;
+                        continue;
+                    }
+                }
+            }
+
+            if state.size < self.max_sessions {
+                state.size += 1;
+                let session =
+                    parking_lot::MutexGuard::unlocked(&mut state, || new_session(&self.instance));
+                return match session {
+                    Ok(session) => Ok(self.pooled(session)),
+                    Err(err) => {
+                        state.size -= 1;
+                        self.available.notify_one();
+                        Err(err)
+                    }
+                };
+            }
+
+            if self
+                .available
+                .wait_for(&mut state, self.wait_timeout)
+                .timed_out()
+            {
+                return Err(Error::io_timeout(format!(
+                    "no idle ssh session in {} s, max sessions = {}",
+                    self.wait_timeout.as_secs(),
+                    self.max_sessions
+                )));
+            }
+        }
+    }
+
+    fn pooled(&self, session: Session) -> PooledSession {
+        PooledSession {
+            pool: self,
+            session: Some(session),
+            broken: Cell::new(false),
+        }
+    }
+
+    fn release(&self, session: Session) {
+        self.state.lock().idle.push((session, Instant::now()));
+        self.available.notify_one();
+    }
+
+    /// 丢弃连接已经断开的会话，空出的位置可以重新建立连接
+    fn discard(&self, session: Session) {
+        drop(session);
+        self.state.lock().size -= 1;
+        self.available.notify_one();
+    }
+}
+
+impl<'a> PooledSession<'a> {
+    /// 检查使用会话的结果，出现 libssh 的错误或者连接中断、超时等 IO 错误时将会话标记为已断开
+    pub fn check<T>(&self, result: Result<T>) -> Result<T> {
+        if let Err(err) = &result {
+            if is_connection_error(err) {
+                warn!("ssh session is broken - {}", err);
+                self.broken.set(true);
+            }
+        }
+        result
+    }
+}
+
+impl<'a> Deref for PooledSession<'a> {
+    type Target = Session;
+    fn deref(&self) -> &Session {
+        self.session.as_ref().unwrap()
+    }
+}
+
+impl<'a> Drop for PooledSession<'a> {
+    fn drop(&mut self) {
+        if let Some(session) = self.session.take() {
+            if self.broken.get() {
+                self.pool.discard(session);
+            } else {
+                self.pool.release(session);
+            }
+        }
+    }
+}
+
+/// 通过打开一个通道来检查会话的连接是否可用
+fn is_alive(session: &Session) -> bool {
+    match session.channel_new() {
+        Ok(mut channel) => channel.open_session().is_ok(),
+        Err(_) => false,
+    }
+}
+
+#[test]
+fn test() {
+    let (pool, _) = super::new_test_sess().unwrap();
+    let first = pool.get().unwrap();
+    let second = pool.get().unwrap();
+    drop(first);
+    drop(second);
+    assert_eq!(2, pool.state.lock().idle.len());
+
+    // 会话数量达到上限后等待超时
+    let instance: Instance = format!("sqlite:{}&max_sessions=1", super::get_remote_uri())
+        .parse()
+        .unwrap();
+    let pool = SessionPool::new(&instance).unwrap();
+    let session = pool.get().unwrap();
+    assert!(pool.get().is_err());
+    drop(session);
+    assert!(pool.get().is_ok());
+}
+
+#[test]
+fn test_broken() {
+    use super::run_command;
+    let instance: Instance = format!("sqlite:{}&max_sessions=1", super::get_remote_uri())
+        .parse()
+        .unwrap();
+    let pool = SessionPool::new(&instance).unwrap();
+    // 获取会话所在的 sshd 进程，归还后该会话在 IDLE_CHECK 之内不会再检查
+    let session = pool.get().unwrap();
+    let output = run_command(&session, "echo $PPID", 5).unwrap();
+    let pid = String::from_utf8_lossy(&output).trim().to_owned();
+    drop(session);
+
+    // 通过另一个连接终止该会话的 sshd 进程
+    let (other, _) = super::new_test_sess().unwrap();
+    let _ = run_command(&other.get().unwrap(), &format!("kill {}", pid), 5);
+    std::thread::sleep(Duration::from_millis(200));
+
+    let session = pool.get().unwrap();
+    assert!(run_command(&session, "echo bee", 5).is_err());
+    drop(session);
+    assert_eq!(0, pool.state.lock().size);
+    assert!(pool.state.lock().idle.is_empty());
+
+    // 重新建立连接
+    let session = pool.get().unwrap();
+    assert_eq!(
+        b"bee\n".to_vec(),
+        run_command(&session, "echo bee", 5).unwrap()
+    );
+}
@@ -1,3 +1,4 @@
+use super::SessionPool;
 use crate::{value::Bytes, Columns, Promise, Result, Row, ToData, ToType};
 use ssh::{Session, SftpFile};
 use std::{
@@ -13,7 +14,19 @@ pub struct FileBytes {
 
 #[datasource]
 pub fn read_file(
-    session: Arc<Session>,
+    pool: Arc<SessionPool>,
+    path: String,
+    start_index: i64,
+    size: i64,
+    promise: &mut Promise<FileBytes>,
+) -> Result<()> {
+    let session = pool.get()?;
+    session.check(read_remote(&session, path, start_index, size, promise))
+}
+
+/// 通过 sftp 读取文件中指定范围的内容
+fn read_remote(
+    session: &Session,
     path: String,
     start_index: i64,
     size: i64,
@@ -84,11 +97,11 @@ fn read_commit(
 fn test() {
     use crate::*;
     const PATH: &str = "/etc/hosts";
-    let (session, _) = super::new_test_sess().unwrap();
+    let (pool, _) = super::new_test_sess().unwrap();
     let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
     async_std::task::spawn_blocking(move || {
         let mut promise = req.head::<FileBytes>().unwrap();
-        if let Err(err) = read_file(session, PATH.to_string(), 2, 5, &mut promise) {
+        if let Err(err) = read_file(pool, PATH.to_string(), 2, 5, &mut promise) {
             let _ = req.error(err);
         } else {
             let _ = req.ok();
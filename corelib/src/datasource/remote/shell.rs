@@ -1,111 +1,149 @@
+use super::{exec, is_posix, quote, SessionPool, Stream};
 use crate::datasource::BashRow;
 use crate::{Error, Instance, Promise, Result, ToData, ToType};
-use ssh::Session;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::Instant;
 
-#[datasource]
+#[datasource(filter(line_num, stream))]
 fn shell(
     instance: Arc<Instance>,
-    session: Arc<Session>,
+    pool: Arc<SessionPool>,
     script: String,
     timeout: u32,
+    cwd: Option<String>,
+    user: Option<String>,
+    input: Option<String>,
+    all_streams: Option<bool>,
     promise: &mut Promise<BashRow>,
 ) -> Result<()> {
+    let all_streams = all_streams.unwrap_or(false);
+    let session = pool.get()?;
     info!("ssh [{}] with timeout = {}s", script, timeout);
-    let mut channel = session.channel_new()?;
-    channel.open_session()?;
-    let mark = std::thread::current().id();
-    // 起始标示
-    let mark_start = format!("#{:?}", mark);
-    // 结束标示
-    let mark_end = format!("{:?}#", mark);
-
-    let mark_start_cmd = format!("echo '{}'", mark_start);
-    let mark_end_cmd = format!("echo '{}'", mark_end);
-    let mut real_script = format!(
-        "{};echo '';{};echo '';{};",
-        mark_start_cmd, script, mark_end_cmd
-    );
-
+    let script = wrap_script(&instance, script, cwd, user, input);
+    let posix = is_posix(&instance);
     // 设置环境变量
-    real_script = set_env(instance, &real_script);
-
-    channel.request_exec(real_script.as_bytes())?;
-
-    let mut stdout = channel.stdout();
+    let script = set_env(instance, &script);
+    let start = Instant::now();
 
-    let mut buffer: String = String::new();
-
-    loop {
-        let mut buf = [0u8; 1024];
-        let size = stdout.read_timeout(&mut buf, Duration::from_secs(timeout as u64))?;
+    // 读取到完整的一行就立即提交，不再等待命令执行结束
+    let mut stdout = LineDecoder::new(Stream::Stdout);
+    let mut stderr = LineDecoder::new(Stream::Stderr);
+    let exit_code = exec(&session, &script, timeout, posix, |stream, data| {
+        // 请求被取消后关闭通道，不再等待命令执行结束
+        if promise.is_cancelled() {
+            return Err(Error::cancelled("the remote command is cancelled"));
+        }
+        match stream {
+            Stream::Stdout => stdout.decode(data, promise),
+            Stream::Stderr if all_streams => stderr.decode(data, promise),
+            // 标准错误默认只读取不返回
+            Stream::Stderr => Ok(()),
+        }
+    })?;
+    stdout.finish(promise)?;
+    if all_streams {
+        stderr.finish(promise)?;
+        promise.commit(BashRow::status(exit_code as i64, start.elapsed()))?;
+    }
 
-        if size > 0 {
-            let slice = &buf[0..size];
-            let rs = std::str::from_utf8(slice)?;
+    return Ok(());
+}
 
-            buffer += rs;
+/// 按行解析命令的输出，无法按 UTF-8 解析的内容会被替换为 `U+FFFD`，空白行会被忽略
+struct LineDecoder {
+    stream: Stream,
+    line_num: u32,
+    // 尚未读取到换行符的数据
+    pending: Vec<u8>,
+}
 
-            if buffer.contains(&format!("\n{}", mark_end)) {
-                break;
-            }
-        } else {
-            channel.send_eof()?;
-            return Err(Error::io_timeout(format!(
-                "cmd - [{}] is timeout in {} s",
-                script, timeout
-            )));
+impl LineDecoder {
+    fn new(stream: Stream) -> Self {
+        Self {
+            stream,
+            line_num: 0,
+            pending: vec![],
         }
     }
 
-    channel.send_eof()?;
-    decode_output(mark_start, mark_end, buffer, promise)?;
-
-    return Ok(());
-}
+    /// 解析读取到的数据，每读取到完整的一行就提交
+    fn decode(&mut self, data: &[u8], promise: &mut Promise<BashRow>) -> Result<()> {
+        self.pending.extend_from_slice(data);
+        while let Some(index) = self.pending.iter().position(|ch| *ch == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=index).collect();
+            self.commit(&line[..index], promise)?;
+        }
+        Ok(())
+    }
 
-fn decode_output(
-    mark_start: String,
-    mark_end: String,
-    buffer: String,
-    promise: &mut Promise<BashRow>,
-) -> crate::Result<()> {
-    let lines = buffer.lines();
-    let mut has_start = false;
-
-    let mut index = 0;
-    for line in lines {
-        // 匹配起始行
-        if line.trim() == mark_start {
-            has_start = true;
-            continue;
+    /// 提交最后一行不以换行符结尾的内容
+    fn finish(&mut self, promise: &mut Promise<BashRow>) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
         }
+        let line = std::mem::take(&mut self.pending);
+        self.commit(&line, promise)
+    }
 
-        // 匹配结束行，结束行需要前面两个条件满足后
-        if line.trim() == mark_end && has_start {
+    /// 提交一行内容，与之前的版本一致，空白行不会提交也不计入行号
+    fn commit(&mut self, line: &[u8], promise: &mut Promise<BashRow>) -> Result<()> {
+        let line = String::from_utf8_lossy(line);
+        if line.trim().is_empty() {
             return Ok(());
         }
+        let line = line.trim_end_matches('\r').to_owned();
+        let row = match self.stream {
+            Stream::Stdout => BashRow::stdout(line, self.line_num),
+            Stream::Stderr => BashRow::stderr(line, self.line_num),
+        };
+        self.line_num += 1;
+        promise.commit(row)
+    }
+}
 
-        if has_start && !line.trim().is_empty() {
-            promise.commit(BashRow {
-                line: line.to_string(),
-                line_num: index,
-            })?;
-            index += 1;
+/// 根据工作目录、执行用户以及标准输入包装需要执行的脚本
+fn wrap_script(
+    instance: &Arc<Instance>,
+    script: String,
+    cwd: Option<String>,
+    user: Option<String>,
+    input: Option<String>,
+) -> String {
+    if cwd.is_none() && user.is_none() && input.is_none() {
+        return script;
+    }
+    // 在子 shell 中执行，切换目录失败时只退出子 shell
+    let inner = match cwd {
+        Some(cwd) => format!("cd {} || exit 1\n{}", quote(&cwd), script),
+        None => script,
+    };
+    let runner = match user {
+        Some(user) => {
+            // sudo 会重置环境变量，需要在切换用户后重新设置
+            let inner = quote(&set_env(instance.clone(), &inner));
+            let user = quote(&user);
+            format!(
+                "if [ \"$(id -u)\" -eq 0 ]; then su {user} -s /bin/sh -c {inner}; else sudo -n -u {user} sh -c {inner}; fi",
+                user = user,
+                inner = inner
+            )
         }
+        None => format!("sh -c {}", quote(&inner)),
+    };
+    match input {
+        Some(input) => format!("printf '%s' {} | {}", quote(&input), runner),
+        None => runner,
     }
-    return Ok(());
 }
 
 fn set_env(instance: Arc<Instance>, script: &String) -> String {
     let mut env_str = String::new();
-    let os_version: String = instance.get_param("os_version").unwrap_or_default();
+    let posix = is_posix(&instance);
     for (key, val) in instance.environments.iter() {
-        if os_version == "windows" {
-            env_str.push_str(&format!("set {}={};", key, val));
-        } else {
+        if posix {
             env_str.push_str(&format!("export {}={};", key, val));
+        } else {
+            env_str.push_str(&format!("set {}={};", key, val));
         }
     }
     env_str.push_str(script);
@@ -116,15 +154,19 @@ fn set_env(instance: Arc<Instance>, script: &String) -> String {
 #[test]
 fn test() {
     use crate::*;
-    let (session, instance) = super::new_test_sess().unwrap();
+    let (pool, instance) = super::new_test_sess().unwrap();
     let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
     async_std::task::spawn_blocking(move || {
         let mut promise = req.head::<BashRow>().unwrap();
         if let Err(err) = shell(
             instance,
-            session,
-            "echo $ORACLE_SID".to_owned(),
+            pool,
+            "echo $ORACLE_SID;echo error >&2;exit 3".to_owned(),
             2,
+            None,
+            None,
+            None,
+            Some(true),
             &mut promise,
         ) {
             let _ = req.error(err);
@@ -135,36 +177,124 @@ fn test() {
 
     let resp = resp.wait().unwrap();
     assert_eq!(
-        &columns![String: "line",Integer: "line_num"],
+        &columns![String: "line", Integer: "line_num", String: "stream", Integer: "exit_code", Integer: "elapsed_ms"],
         resp.columns()
     );
 
-    let mut index = 0;
+    let mut rows = vec![];
     for row in resp {
         let row = row.unwrap();
         let line: String = row.get(0).unwrap();
-        let line_num: i64 = row.get(1).unwrap();
+        let stream: String = row.get(2).unwrap();
+        rows.push((line, stream, row.get::<i64>(3).ok()));
+    }
+    // 标准输出与标准错误分别读取，两个流之间的先后顺序不确定
+    let lines = |stream: &str| -> Vec<(String, Option<i64>)> {
+        rows.iter()
+            .filter(|(_, name, _)| name == stream)
+            .map(|(line, _, code)| (line.clone(), *code))
+            .collect()
+    };
+    assert_eq!(vec![("XE".to_owned(), None)], lines("stdout"));
+    assert_eq!(vec![("error".to_owned(), None)], lines("stderr"));
+    assert_eq!(vec![("".to_owned(), Some(3))], lines("status"));
+}
+
+#[test]
+fn test_wrap_script() {
+    let instance = Arc::new(
+        Instance::from("sqlite:remote:default://127.0.0.1:22/bee?environments=[ORACLE_SID: XE]")
+            .unwrap(),
+    );
+    let script = "echo $ORACLE_SID".to_owned();
+    assert_eq!(
+        script,
+        wrap_script(&instance, script.clone(), None, None, None)
+    );
+
+    let script = wrap_script(
+        &instance,
+        "pwd;cat".to_owned(),
+        Some("/tmp".to_owned()),
+        None,
+        Some("it's".to_owned()),
+    );
+    let output = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(script)
+        .output()
+        .unwrap();
+    assert_eq!("/tmp\nit's", String::from_utf8_lossy(&output.stdout));
+
+    let script = wrap_script(
+        &instance,
+        "id".to_owned(),
+        None,
+        Some("oracle".to_owned()),
+        None,
+    );
+    assert!(script.contains("sudo -n -u 'oracle' sh -c 'export ORACLE_SID=XE;id'"));
+}
 
-        assert_eq!("XE".to_owned(), line);
-        assert_eq!(0, line_num);
-        index += 1;
+#[test]
+fn test_decode() {
+    use crate::*;
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    {
+        let mut promise = req.head::<BashRow>().unwrap();
+        let mut stdout = LineDecoder::new(Stream::Stdout);
+        let mut stderr = LineDecoder::new(Stream::Stderr);
+        // 多字节字符被拆分到两次读取中，空白行被忽略
+        stdout
+            .decode(b"XE\r\n\n \r\nOR\xE4\xBD", &mut promise)
+            .unwrap();
+        stderr.decode(b"error\n", &mut promise).unwrap();
+        stdout.decode(b"\xA0CL\n\xFF", &mut promise).unwrap();
+        stdout.finish(&mut promise).unwrap();
+        stderr.finish(&mut promise).unwrap();
     }
-    assert!(index > 0);
+    drop(req);
+
+    let rows: Vec<(String, i64, String)> = resp
+        .wait()
+        .unwrap()
+        .map(|row| {
+            let row = row.unwrap();
+            (
+                row.get(0).unwrap(),
+                row.get(1).unwrap(),
+                row.get(2).unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            ("XE".to_owned(), 0, "stdout".to_owned()),
+            ("error".to_owned(), 0, "stderr".to_owned()),
+            ("OR你CL".to_owned(), 1, "stdout".to_owned()),
+            ("\u{FFFD}".to_owned(), 2, "stdout".to_owned()),
+        ],
+        rows
+    );
 }
 
 #[test]
 #[should_panic(expected = "timeout")]
 fn test_timeout() {
     use crate::*;
-    let (session, instance) = super::new_test_sess().unwrap();
+    let (pool, instance) = super::new_test_sess().unwrap();
     let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
     {
         let mut promise = req.head::<BashRow>().unwrap();
         if let Err(err) = shell(
             instance,
-            session,
-            "sleep(5),echo $ORACLE_SID".to_owned(),
+            pool,
+            "sleep 5;echo $ORACLE_SID".to_owned(),
             2,
+            None,
+            None,
+            None,
+            None,
             &mut promise,
         ) {
             let _ = req.error(err);
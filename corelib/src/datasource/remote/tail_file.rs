@@ -0,0 +1,139 @@
+use super::{quote, run_command, PooledSession, SessionPool, COMMAND_TIMEOUT, FILE_CHANGED};
+use crate::datasource::{commit_lines, tail_offset, FileLine};
+use crate::{Error, Promise, Result, ToData, ToType};
+use std::io::{BufReader, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// 打开文件时文件被轮转后重试的次数
+const OPEN_RETRIES: usize = 3;
+
+#[datasource]
+pub fn tail_file(
+    pool: Arc<SessionPool>,
+    path: String,
+    from_offset: i64,
+    max_lines: Option<u32>,
+    last_inode: Option<i64>,
+    promise: &mut Promise<FileLine>,
+) -> Result<()> {
+    let session = pool.get()?;
+    session.check(tail(
+        &session,
+        path,
+        from_offset,
+        max_lines,
+        last_inode,
+        promise,
+    ))
+}
+
+/// 通过 sftp 从读取位置开始按行读取文件
+fn tail(
+    session: &PooledSession,
+    path: String,
+    from_offset: i64,
+    max_lines: Option<u32>,
+    last_inode: Option<i64>,
+    promise: &mut Promise<FileLine>,
+) -> Result<()> {
+    let mut sftp = session.sftp_new()?;
+    sftp.init()?;
+    let file_path: PathBuf = path.parse()?;
+
+    // SFTP 不返回文件的 inode，在打开文件的前后各获取一次 inode，
+    // 两次一致时说明打开的文件就是该 inode 对应的文件，文件大小从同一个句柄获取
+    let mut retries = 0;
+    let (mut file, inode) = loop {
+        let inode = stat_inode(session, &path)?;
+        let file = sftp.open(&file_path, libc::O_RDONLY as usize, 0)?;
+        if stat_inode(session, &path)? == inode {
+            break (file, inode);
+        }
+        retries += 1;
+        if retries >= OPEN_RETRIES {
+            return Err(Error::other(
+                FILE_CHANGED,
+                format!("file - {} is rotated while opening", path),
+            ));
+        }
+    };
+    let file_size = file.get_size();
+    let (offset, skip_partial) = tail_offset(from_offset, file_size, inode, last_inode);
+    debug!(
+        "tail file [{}] from {} with inode = {}",
+        path, offset, inode
+    );
+
+    // 通过 SFTP 从读取位置开始按行读取，读取到 max_lines 行后不再继续读取
+    file.seek(SeekFrom::Start(offset))?;
+    commit_lines(
+        BufReader::new(file),
+        offset,
+        skip_partial,
+        inode,
+        max_lines,
+        promise,
+    )
+}
+
+/// 获取文件的 inode，符号链接获取其指向的文件
+fn stat_inode(session: &PooledSession, path: &str) -> Result<u64> {
+    let output = run_command(
+        session,
+        &format!("stat -L -c '%i' {}", quote(path)),
+        COMMAND_TIMEOUT,
+    )?;
+    decode_inode(&String::from_utf8_lossy(&output))
+}
+
+/// 解析 `stat -c '%i'` 的输出
+fn decode_inode(output: &str) -> Result<u64> {
+    output
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| Error::invalid_type(format!("invalid stat output - {}", output)))
+}
+
+#[test]
+fn test_decode_inode() {
+    assert_eq!(1234, decode_inode("1234\n").unwrap());
+    assert!(decode_inode("stat: cannot stat").is_err());
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    let (pool, _) = super::new_test_sess().unwrap();
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<FileLine>().unwrap();
+        if let Err(err) = tail_file(
+            pool,
+            "/etc/hosts".to_owned(),
+            0,
+            Some(1),
+            None,
+            &mut promise,
+        ) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "line", Integer: "line_num", Integer: "byte_offset", Integer: "inode"],
+        resp.columns()
+    );
+
+    let mut index = 0;
+    for row in resp {
+        let row = row.unwrap();
+        let byte_offset: i64 = row.get(2).unwrap();
+        assert!(byte_offset > 0);
+        index += 1;
+    }
+    assert_eq!(1, index);
+}
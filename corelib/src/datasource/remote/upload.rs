@@ -0,0 +1,90 @@
+use super::{local_path, parse_mode, set_permission, SessionPool};
+use crate::{datasource::FileTransfer, Instance, Promise, Result, ToData, ToType};
+use ssh::Session;
+use std::{path::PathBuf, sync::Arc};
+
+#[datasource]
+pub fn upload(
+    instance: Arc<Instance>,
+    pool: Arc<SessionPool>,
+    local: String,
+    remote: String,
+    mode: Option<String>,
+    promise: &mut Promise<FileTransfer>,
+) -> Result<()> {
+    let session = pool.get()?;
+    let mode = mode.as_deref().map(parse_mode).transpose()?;
+    let mut source = std::fs::File::open(local_path(&instance, &local)?)?;
+    let size = session.check(upload_file(&session, &mut source, &remote, mode))?;
+    info!("upload file - {} to {} with size = {}", local, remote, size);
+
+    set_permission(&session, &remote, mode, None)?;
+    promise.commit(FileTransfer {
+        source: local,
+        target: remote,
+        size: size as i64,
+    })?;
+    Ok(())
+}
+
+/// 通过 sftp 上传文件内容，返回上传的字节数
+fn upload_file(
+    session: &Session,
+    source: &mut std::fs::File,
+    remote: &str,
+    mode: Option<u32>,
+) -> Result<u64> {
+    let mut sftp = session.sftp_new()?;
+    sftp.init()?;
+    let file_path: PathBuf = remote.parse()?;
+    let flags = libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC;
+    let mut target = sftp.open(&file_path, flags as usize, mode.unwrap_or(0o644) as usize)?;
+    Ok(std::io::copy(source, &mut target)?)
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    const LOCAL: &str = "/tmp/bee_upload_local.sh";
+    const REMOTE: &str = "/tmp/bee_upload_remote.sh";
+    std::fs::write(LOCAL, "#!/bin/sh\necho hello\n").unwrap();
+
+    let (pool, instance) = super::new_test_sess().unwrap();
+    let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+    async_std::task::spawn_blocking(move || {
+        let mut promise = req.head::<FileTransfer>().unwrap();
+        if let Err(err) = upload(
+            instance,
+            pool,
+            LOCAL.to_owned(),
+            REMOTE.to_owned(),
+            Some("0755".to_owned()),
+            &mut promise,
+        ) {
+            let _ = req.error(err);
+        } else {
+            let _ = req.ok();
+        }
+    });
+
+    let resp = resp.wait().unwrap();
+    assert_eq!(
+        &columns![String: "source", String: "target", Integer: "size"],
+        resp.columns()
+    );
+
+    let mut index = 0;
+    for row in resp {
+        let row = row.unwrap();
+        let size: i64 = row.get(2).unwrap();
+        assert_eq!(21, size);
+        index += 1;
+    }
+    assert_eq!(1, index);
+    assert_eq!(
+        std::fs::read(LOCAL).unwrap(),
+        std::fs::read(REMOTE).unwrap()
+    );
+    std::fs::remove_file(LOCAL).unwrap();
+    std::fs::remove_file(REMOTE).unwrap();
+}
@@ -0,0 +1,99 @@
+use super::{parse_mode, set_permission, SessionPool};
+use crate::{datasource::Status, value::Bytes, Promise, Result, ToData, ToType};
+use ssh::Session;
+use std::io::{Seek, SeekFrom, Write};
+use std::{path::PathBuf, sync::Arc};
+
+#[datasource]
+pub fn write_bytes(
+    pool: Arc<SessionPool>,
+    path: String,
+    content: Bytes,
+    append: Option<bool>,
+    mode: Option<String>,
+    owner: Option<String>,
+    promise: &mut Promise<Status>,
+) -> Result<()> {
+    let session = pool.get()?;
+    let append = append.unwrap_or(false);
+    let mode = mode.as_deref().map(parse_mode).transpose()?;
+
+    session.check(write_content(&session, &path, &content, append, mode))?;
+
+    set_permission(&session, &path, mode, owner.as_deref())?;
+    promise.commit(Status { success: true })?;
+    Ok(())
+}
+
+/// 通过 sftp 写入文件内容
+fn write_content(
+    session: &Session,
+    path: &str,
+    content: &[u8],
+    append: bool,
+    mode: Option<u32>,
+) -> Result<()> {
+    let mut sftp = session.sftp_new()?;
+    sftp.init()?;
+    let file_path: PathBuf = path.parse()?;
+    let flags = if append {
+        libc::O_WRONLY | libc::O_CREAT
+    } else {
+        libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC
+    };
+    let mut file = sftp.open(&file_path, flags as usize, mode.unwrap_or(0o644) as usize)?;
+    // 部分 sftp 服务端不支持 O_APPEND，追加时从文件末尾开始写入
+    if append {
+        let _ = file.seek(SeekFrom::End(0))?;
+    }
+    file.write_all(content)?;
+    Ok(())
+}
+
+#[test]
+fn test() {
+    use crate::*;
+    const PATH: &str = "/tmp/bee_write_bytes.bin";
+    let (pool, _) = super::new_test_sess().unwrap();
+    let write = |content: Bytes, append: bool| {
+        let pool = pool.clone();
+        let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
+        async_std::task::spawn_blocking(move || {
+            let mut promise = req.head::<Status>().unwrap();
+            if let Err(err) = write_bytes(
+                pool,
+                PATH.to_owned(),
+                content,
+                Some(append),
+                Some("0600".to_owned()),
+                None,
+                &mut promise,
+            ) {
+                let _ = req.error(err);
+            } else {
+                let _ = req.ok();
+            }
+        });
+
+        let resp = resp.wait().unwrap();
+        assert_eq!(&columns![Boolean: "success"], resp.columns());
+        for row in resp {
+            let success: bool = row.unwrap().get(0).unwrap();
+            assert!(success);
+        }
+    };
+
+    write(vec![0x00, 0xff], false);
+    write(vec![0x01], true);
+    assert_eq!(vec![0x00, 0xff, 0x01], std::fs::read(PATH).unwrap());
+    write(vec![0x02], false);
+    assert_eq!(vec![0x02], std::fs::read(PATH).unwrap());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(PATH).unwrap().permissions().mode();
+        assert_eq!(0o600, mode & 0o7777);
+    }
+    std::fs::remove_file(PATH).unwrap();
+}
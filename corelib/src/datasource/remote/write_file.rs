@@ -1,34 +1,41 @@
+use super::SessionPool;
 use crate::{datasource::Status, Promise, Result, ToData, ToType};
 use ssh::Session;
 use std::{io::Write, path::PathBuf, sync::Arc};
 
 #[datasource]
 pub fn write_file(
-    session: Arc<Session>,
+    pool: Arc<SessionPool>,
     base_path: String,
     path: String,
     content: String,
     promise: &mut Promise<Status>,
 ) -> Result<()> {
+    let session = pool.get()?;
+    session.check(push_file(&session, &base_path, &path, &content))?;
+    promise.commit(Status { success: true })?;
+    Ok(())
+}
+
+/// 通过 scp 写入文件内容
+fn push_file(session: &Session, base_path: &str, path: &str, content: &str) -> Result<()> {
     let path: PathBuf = path.parse()?;
     let mut channel = session.scp_new(ssh::Mode::WRITE, base_path)?;
     channel.init()?;
     channel.push_file(path, content.len(), 0o644)?;
     let _ = channel.write(content.as_bytes())?;
-    drop(channel);
-    promise.commit(Status { success: true })?;
     Ok(())
 }
 
 #[test]
 fn test() {
     use crate::*;
-    let (session, _) = super::new_test_sess().unwrap();
+    let (pool, _) = super::new_test_sess().unwrap();
     let (req, resp) = crate::new_req(crate::Args::new(), std::time::Duration::from_secs(2));
     async_std::task::spawn_blocking(move || {
         let mut promise = req.head::<Status>().unwrap();
         if let Err(err) = write_file(
-            session,
+            pool,
             "/tmp".to_string(),
             "test.log".to_owned(),
             "hello world".to_string(),
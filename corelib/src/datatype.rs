@@ -42,6 +42,12 @@ impl_to_type!(Bytes, Bytes);
 impl_to_type!([u8], Bytes);
 impl_to_type!((), Nil);
 
+impl<T: ToType> ToType for Option<T> {
+    fn get_type() -> DataType {
+        T::get_type()
+    }
+}
+
 impl FromStr for DataType {
     type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -161,4 +167,6 @@ fn test() {
         DataType::from(Value::from(b"\x01\x02".to_vec()))
     );
     assert_eq!(DataType::Nil, DataType::from(Value::Nil));
+
+    assert_eq!(DataType::Integer, Option::<u32>::get_type());
 }
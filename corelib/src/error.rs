@@ -46,6 +46,7 @@ const IO_WOULDBLOCK: i32 = code!(IO, 17);
 const IO_INTERRUPTED: i32 = code!(IO, 18);
 const CHANNEL_RECV: i32 = code!(CHANNEL, 1);
 const CHANNEL_SEND: i32 = code!(CHANNEL, 2);
+const CHANNEL_CANCELLED: i32 = code!(CHANNEL, 3);
 
 const MUTEX_LOCKED: i32 = code!(LOCKED, 1);
 
@@ -75,6 +76,7 @@ impl Error {
     from_code!(index_param, PARAM_INDEX, &str);
     from_code!(io_timeout, IO_TIMEDOUT, String);
     from_code!(lock_faild, LOCKED, &str);
+    from_code!(cancelled, CHANNEL_CANCELLED, &str);
 
     pub fn new<T: ToString>(code: i32, msg: T) -> Self {
         Self {
@@ -115,6 +117,24 @@ impl Error {
     pub fn ok_code() -> i32 {
         OK
     }
+
+    /// 是否为请求被取消的错误
+    pub fn is_cancelled(&self) -> bool {
+        self.code == CHANNEL_CANCELLED
+    }
+
+    /// 是否为连接中断或者超时的 IO 错误
+    pub fn is_disconnected(&self) -> bool {
+        matches!(
+            self.code,
+            IO_CONNECTIONRESET
+                | IO_CONNECTIONABORTED
+                | IO_NOTCONNECTED
+                | IO_BROKENPIPE
+                | IO_UNEXPECTEDEOF
+                | IO_TIMEDOUT
+        )
+    }
 }
 
 macro_rules! from_error {
@@ -0,0 +1,225 @@
+use crate::{Columns, Row, Value};
+use std::cmp::Ordering;
+
+/// 过滤条件的比较操作，`IN` 不会下推，避免脚本引擎按照每个值重复执行数据源
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FilterOp {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Like,
+}
+
+/// 下推到数据源的过滤条件
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    column: String,
+    op: FilterOp,
+    value: Value,
+}
+
+impl Filter {
+    /// 创建一个过滤条件
+    pub fn new<K: Into<String>>(column: K, op: FilterOp, value: Value) -> Self {
+        Self {
+            column: column.into(),
+            op,
+            value,
+        }
+    }
+
+    /// 获取过滤的列名
+    #[inline(always)]
+    pub fn get_column(&self) -> &str {
+        &self.column
+    }
+
+    /// 获取比较操作
+    #[inline(always)]
+    pub fn get_op(&self) -> FilterOp {
+        self.op
+    }
+
+    /// 获取比较的值
+    #[inline(always)]
+    pub fn get_value(&self) -> &Value {
+        &self.value
+    }
+
+    /// 判断值是否满足过滤条件
+    ///
+    /// 无法比较的类型视为满足条件，脚本引擎会对结果行再次过滤，数据源只需要保证不会丢弃满足条件的行
+    pub fn matches(&self, value: &Value) -> bool {
+        if value.is_nil() || self.value.is_nil() {
+            return false;
+        }
+        if self.op == FilterOp::Like {
+            return match (value, &self.value) {
+                (Value::String(value), Value::String(pattern)) => like(pattern, value),
+                _ => true,
+            };
+        }
+        let ordering = match compare(value, &self.value) {
+            Some(ordering) => ordering,
+            None => return true,
+        };
+        match self.op {
+            FilterOp::Eq => ordering == Ordering::Equal,
+            FilterOp::Gt => ordering == Ordering::Greater,
+            FilterOp::Ge => ordering != Ordering::Less,
+            FilterOp::Lt => ordering == Ordering::Less,
+            FilterOp::Le => ordering != Ordering::Greater,
+            FilterOp::Like => unreachable!(),
+        }
+    }
+}
+
+/// 过滤条件列表，所有条件之间为 `AND` 关系
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Filters {
+    values: Vec<Filter>,
+}
+
+impl Filters {
+    /// 创建一个空的过滤条件列表
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self { values: vec![] }
+    }
+
+    /// 添加一个过滤条件
+    #[inline(always)]
+    pub fn push(&mut self, filter: Filter) {
+        self.values.push(filter);
+    }
+
+    /// 是否没有任何过滤条件
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// 遍历所有过滤条件
+    pub fn iter(&self) -> impl Iterator<Item = &Filter> {
+        self.values.iter()
+    }
+
+    /// 判断指定列的值是否满足该列上的所有过滤条件，数据源可以在生成结果行之前调用
+    pub fn matches(&self, column: &str, value: &Value) -> bool {
+        self.values
+            .iter()
+            .filter(|filter| filter.column == column)
+            .all(|filter| filter.matches(value))
+    }
+
+    /// 判断结果行是否满足所有的过滤条件
+    pub fn matches_row(&self, columns: &Columns, row: &Row) -> bool {
+        self.values.iter().all(|filter| {
+            match columns
+                .get_index(filter.column.as_str())
+                .and_then(|index| row.get_value(index).ok())
+            {
+                Some(value) => filter.matches(value),
+                None => true,
+            }
+        })
+    }
+}
+
+/// 按照 Sqlite 的规则比较两个值，数值之间按照大小比较，Boolean 视为 0 或者 1
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    let number = |value: &Value| match value {
+        Value::Integer(val) => Some(*val as f64),
+        Value::Number(val) => Some(*val),
+        Value::Boolean(val) => Some(*val as i64 as f64),
+        _ => None,
+    };
+    match (left, right) {
+        (Value::Integer(left), Value::Integer(right)) => Some(left.cmp(right)),
+        (Value::String(left), Value::String(right)) => Some(left.cmp(right)),
+        (Value::Bytes(left), Value::Bytes(right)) => Some(left.cmp(right)),
+        _ => number(left)?.partial_cmp(&number(right)?),
+    }
+}
+
+/// LIKE 匹配，`%` 匹配任意个字符，`_` 匹配单个字符
+///
+/// 所有字符都不区分大小写，比 Sqlite 默认只忽略 ASCII 字符的大小写更宽松，
+/// 开启 `PRAGMA case_sensitive_like` 或者使用 ICU 扩展时也不会丢弃满足条件的行
+fn like(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    // 记录最近一次 `%` 的位置，匹配失败时从该位置回溯
+    let (mut p, mut v) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while v < value.len() {
+        if p < pattern.len() && pattern[p] == '%' {
+            backtrack = Some((p, v));
+            p += 1;
+        } else if p < pattern.len() && (pattern[p] == '_' || eq_ignore_case(pattern[p], value[v])) {
+            p += 1;
+            v += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            v = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|ch| *ch == '%')
+}
+
+/// 不区分大小写比较两个字符
+fn eq_ignore_case(left: char, right: char) -> bool {
+    left == right || left.to_lowercase().eq(right.to_lowercase())
+}
+
+#[test]
+fn test_filter() {
+    let filter = Filter::new("pid", FilterOp::Gt, Value::from(10));
+    assert!(filter.matches(&Value::from(11)));
+    assert!(filter.matches(&Value::from(10.5)));
+    assert!(!filter.matches(&Value::from(10)));
+    assert!(!filter.matches(&Value::Nil));
+    assert!(filter.matches(&Value::from("text")));
+
+    let filter = Filter::new("name", FilterOp::Le, Value::from("b"));
+    assert!(filter.matches(&Value::from("a")));
+    assert!(filter.matches(&Value::from("b")));
+    assert!(!filter.matches(&Value::from("c")));
+
+    let filter = Filter::new("read_only", FilterOp::Eq, Value::from(1));
+    assert!(filter.matches(&Value::from(true)));
+    assert!(!filter.matches(&Value::from(false)));
+}
+
+#[test]
+fn test_like() {
+    assert!(like("%sshd%", "/usr/sbin/SSHD -D"));
+    assert!(like("ora_%_orcl", "ora_pmon_orcl"));
+    assert!(like("a%b%c", "aXbYbZc"));
+    assert!(like("%", ""));
+    assert!(!like("ora_%", "ora"));
+    assert!(!like("a_c", "abbc"));
+    assert!(like("É%", "école"));
+}
+
+#[test]
+fn test_filters() {
+    let mut filters = Filters::new();
+    filters.push(Filter::new("line_num", FilterOp::Ge, Value::from(2)));
+    filters.push(Filter::new("line_num", FilterOp::Lt, Value::from(4)));
+    filters.push(Filter::new("line", FilterOp::Like, Value::from("%ora%")));
+
+    assert!(filters.matches("line_num", &Value::from(3)));
+    assert!(!filters.matches("line_num", &Value::from(4)));
+    assert!(filters.matches("stream", &Value::from("stdout")));
+
+    let columns = crate::columns![String: "line", Integer: "line_num"];
+    assert!(filters.matches_row(&columns, &crate::row!["oracle", 2]));
+    assert!(!filters.matches_row(&columns, &crate::row!["grid", 2]));
+    assert!(!filters.matches_row(&columns, &crate::row!["oracle", 1]));
+}
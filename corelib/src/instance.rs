@@ -47,67 +47,16 @@ impl Instance {
                 port = url.port();
                 resource = Some(url.path().replace("/", "").to_string());
 
-                let query = url.query();
-
-                params = if let Some(param_str) = query {
-                    let param_str = percent_encoding::percent_decode_str(param_str)
-                        .decode_utf8_lossy()
-                        .to_string();
-                    let mut params: HashMap<String, Value> = HashMap::new();
-                    let values: Vec<&str> = param_str.split("&").collect();
-                    for value in values {
-                        let value_str: Vec<&str> = value.split("=").collect();
-                        let key = value_str
-                            .get(0)
-                            .ok_or(Error::invalid_type(format!(
-                                "failed to get params form {}",
-                                url
-                            )))?
-                            .to_string();
-                        let value = value_str.get(1);
-                        if key == "environments" {
-                            if let Some(val) = value {
-                                let env_str = val.replace("[", "").replace("]", "");
-                                if !env_str.trim().is_empty() {
-                                    let key_value: Vec<&str> = env_str.split(",").collect();
-                                    for item in key_value {
-                                        let item_val: Vec<&str> = item.split(":").collect();
-                                        let key = item_val
-                                            .get(0)
-                                            .ok_or(Error::invalid_type(format!(
-                                                "failed to get params form {}",
-                                                url
-                                            )))?
-                                            .to_string();
-                                        let val = item_val
-                                            .get(1)
-                                            .ok_or(Error::invalid_type(format!(
-                                                "failed to get params form {}",
-                                                url
-                                            )))?
-                                            .to_string();
-                                        let key = key.trim().to_string();
-                                        let val = val.trim().to_string();
-                                        environments.insert(key, val);
-                                    }
-                                }
-                            }
-                        } else {
-                            let value = if let Some(val) = value {
-                                val.parse::<Value>()?
-                            } else {
-                                Value::Nil
-                            };
-                            let _ = params.insert(key, value);
-                        }
-                    }
-
-                    params
-                } else {
-                    HashMap::new()
-                };
+                if let Some(query) = url.query() {
+                    params = parse_query(query, &mut environments)?;
+                }
             } else {
-                connect_mode = url.to_string();
+                // 无连接信息时也可以通过 `?` 指定参数，例如 `sqlite:agent:default?writable=true`
+                let mut parts = url.splitn(2, '?');
+                connect_mode = parts.next().unwrap_or_default().to_string();
+                if let Some(query) = parts.next() {
+                    params = parse_query(query, &mut environments)?;
+                }
             }
         }
 
@@ -197,6 +146,66 @@ impl Instance {
     }
 }
 
+/// 解析 URI 中的参数，`environments` 参数会被解析为环境变量
+fn parse_query(
+    query: &str,
+    environments: &mut HashMap<String, String>,
+) -> Result<HashMap<String, Value>> {
+    let param_str = percent_encoding::percent_decode_str(query)
+        .decode_utf8_lossy()
+        .to_string();
+    let mut params: HashMap<String, Value> = HashMap::new();
+    let values: Vec<&str> = param_str.split("&").collect();
+    for value in values {
+        let value_str: Vec<&str> = value.split("=").collect();
+        let key = value_str
+            .get(0)
+            .ok_or(Error::invalid_type(format!(
+                "failed to get params form {}",
+                query
+            )))?
+            .to_string();
+        let value = value_str.get(1);
+        if key == "environments" {
+            if let Some(val) = value {
+                let env_str = val.replace("[", "").replace("]", "");
+                if !env_str.trim().is_empty() {
+                    let key_value: Vec<&str> = env_str.split(",").collect();
+                    for item in key_value {
+                        let item_val: Vec<&str> = item.split(":").collect();
+                        let key = item_val
+                            .get(0)
+                            .ok_or(Error::invalid_type(format!(
+                                "failed to get params form {}",
+                                query
+                            )))?
+                            .to_string();
+                        let val = item_val
+                            .get(1)
+                            .ok_or(Error::invalid_type(format!(
+                                "failed to get params form {}",
+                                query
+                            )))?
+                            .to_string();
+                        let key = key.trim().to_string();
+                        let val = val.trim().to_string();
+                        environments.insert(key, val);
+                    }
+                }
+            }
+        } else {
+            let value = if let Some(val) = value {
+                val.parse::<Value>()?
+            } else {
+                Value::Nil
+            };
+            let _ = params.insert(key, value);
+        }
+    }
+
+    Ok(params)
+}
+
 impl FromStr for Instance {
     type Err = Error;
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
@@ -229,4 +238,15 @@ fn test() {
     assert_eq!("sqlite".to_owned(), instance.sess_mode);
     assert_eq!("agent".to_owned(), instance.ds_mode);
     assert_eq!("default".to_owned(), instance.connect_mode);
+
+    let instance = Instance::from(
+        "sqlite:agent:default?db=/tmp/bee.sqlite&writable=true&environments=[ORACLE_SID: XE]",
+    )
+    .unwrap();
+    assert_eq!("default".to_owned(), instance.connect_mode);
+    let db: String = instance.get_param("db").unwrap();
+    assert_eq!("/tmp/bee.sqlite", db);
+    let writable: bool = instance.get_param("writable").unwrap();
+    assert!(writable);
+    assert_eq!(Some("XE".to_string()), instance.get_env("ORACLE_SID"));
 }
@@ -5,6 +5,7 @@ mod value;
 mod args;
 mod columns;
 mod datatype;
+mod filter;
 mod instance;
 mod request;
 
@@ -25,6 +26,7 @@ pub use columns::Columns;
 pub use datasource::*;
 pub use error::Error;
 pub use error::Result;
+pub use filter::{Filter, FilterOp, Filters};
 pub use funcs::*;
 pub use value::Value;
 
@@ -1,12 +1,18 @@
 use crate::state::ToData;
-use crate::{Args, Columns, Error, Result, Row, State, Value};
-use std::sync::mpsc::Sender;
+use crate::{Args, Columns, Error, Filters, Result, Row, State, Value};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc::Sender,
+    Arc,
+};
 
 /// 请求实例，用于异步发送数据流
 #[derive(Clone)]
 pub struct Request {
     args: Args,
+    filters: Filters,
     has_columns: bool,
+    cancelled: Arc<AtomicBool>,
     tx: Sender<State>,
 }
 
@@ -19,15 +25,25 @@ pub struct Promise<'a, T> {
 /// 数据行提交器，用于发送数据信息
 pub struct Committer<'a> {
     args: &'a Args,
+    filters: &'a Filters,
+    cancelled: &'a AtomicBool,
     tx: &'a Sender<State>,
     columns: Columns,
 }
 
 impl<'a> Committer<'a> {
-    /// 提交一个数据状态实例
+    /// 提交一个数据状态实例，不满足过滤条件的数据行会被丢弃
+    ///
+    /// 请求被取消后返回 `cancelled` 错误，数据源应当停止执行
     pub fn commit(&mut self, state: State) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(Error::cancelled("the request is cancelled"));
+        }
         if let State::Process(row) = &state {
             valid_row(&self.columns, &row)?;
+            if !self.filters.matches_row(&self.columns, row) {
+                return Ok(());
+            }
         }
         self.tx.send(state)?;
         Ok(())
@@ -38,6 +54,18 @@ impl<'a> Committer<'a> {
     pub fn get_args(&self) -> &Args {
         &self.args
     }
+
+    /// 获取下推的过滤条件
+    #[inline(always)]
+    pub fn get_filters(&self) -> &Filters {
+        &self.filters
+    }
+
+    /// 请求是否已经被取消，响应被释放后不再需要任何数据行
+    #[inline(always)]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
 }
 
 impl Request {
@@ -45,22 +73,40 @@ impl Request {
     pub fn new(args: Args, tx: Sender<State>) -> Self {
         Self {
             args,
+            filters: Filters::new(),
             tx,
             has_columns: false,
+            cancelled: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// 获取请求的取消标记，由结果集在释放响应时设置
+    #[inline(always)]
+    pub(crate) fn cancel_flag(&self) -> Arc<AtomicBool> {
+        self.cancelled.clone()
+    }
+
+    /// 设置脚本引擎下推的过滤条件
+    pub fn set_filters(&mut self, filters: Filters) {
+        self.filters = filters;
+    }
+
     /// 创建一个提交器，需要给定数据列定义的结构
     pub fn new_commit(&self, columns: Columns) -> Result<Committer> {
         self.tx.send(State::from(columns.clone()))?;
         Ok(Committer {
             tx: &self.tx,
             args: &self.args,
+            filters: &self.filters,
+            cancelled: &self.cancelled,
             columns,
         })
     }
 
     pub fn commit(&mut self, row: Vec<(String, Value)>) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(Error::cancelled("the request is cancelled"));
+        }
         if !self.has_columns {
             let mut columns = Columns::new();
             let mut new_row = Row::new();
@@ -107,6 +153,18 @@ impl Request {
     pub fn get_args(&self) -> &Args {
         &self.args
     }
+
+    /// 获取下推的过滤条件
+    #[inline(always)]
+    pub fn get_filters(&self) -> &Filters {
+        &self.filters
+    }
+
+    /// 请求是否已经被取消
+    #[inline(always)]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
 }
 
 impl<'a, T> Promise<'a, T>
@@ -128,6 +186,18 @@ where
     pub fn get_args(&self) -> &Args {
         &self.inner.args
     }
+
+    /// 获取下推的过滤条件，数据源可以在生成结果行之前提前过滤
+    #[inline(always)]
+    pub fn get_filters(&self) -> &Filters {
+        self.inner.filters
+    }
+
+    /// 请求是否已经被取消，耗时的数据源可以在两次提交之间检查并提前结束
+    #[inline(always)]
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled()
+    }
 }
 
 fn valid_row(columns: &Columns, row: &Row) -> Result<()> {
@@ -154,6 +224,43 @@ fn valid_row(columns: &Columns, row: &Row) -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_filters() {
+    use crate::{Filter, FilterOp};
+    let (mut req, resp) = crate::new_req_none(crate::Args::new());
+    let mut filters = Filters::new();
+    filters.push(Filter::new("age", FilterOp::Gt, Value::from(18)));
+    req.set_filters(filters);
+
+    let mut commit = req.new_commit(crate::columns![Integer: "age"]).unwrap();
+    for age in 16..20 {
+        commit.commit(State::from(crate::row![age])).unwrap();
+    }
+    req.ok().unwrap();
+
+    let rows: Vec<i64> = resp
+        .wait()
+        .unwrap()
+        .map(|row| row.unwrap().get(0).unwrap())
+        .collect();
+    assert_eq!(vec![19], rows);
+}
+
+#[test]
+fn test_cancelled() {
+    let (req, statement) = crate::new_req_none(crate::Args::new());
+    let mut commit = req.new_commit(crate::columns![Integer: "id"]).unwrap();
+    commit.commit(State::from(crate::row![1])).unwrap();
+
+    let resp = statement.wait().unwrap();
+    assert!(!commit.is_cancelled());
+    drop(resp);
+
+    assert!(req.is_cancelled());
+    let err = commit.commit(State::from(crate::row![2])).unwrap_err();
+    assert!(err.is_cancelled());
+}
+
 #[test]
 #[should_panic(expected = "invalid row : the cols len is")]
 fn test_valid_row_len() {
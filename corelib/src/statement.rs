@@ -1,6 +1,10 @@
 use crate::{Args, Columns, Error, Request, Row, State};
 use std::{
-    sync::mpsc::{channel, Receiver, RecvTimeoutError},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, Receiver, RecvTimeoutError},
+        Arc,
+    },
     time::Duration,
 };
 
@@ -10,6 +14,8 @@ pub struct Statement {
     tx: Receiver<State>,
     /// 最大执行时间
     timeout: Option<Duration>,
+    /// 请求的取消标记
+    cancelled: Arc<AtomicBool>,
 }
 
 /// 请求执行后的响应内容
@@ -20,6 +26,8 @@ pub struct Response {
     columns: Columns,
     /// 数据流接收器
     tx: Receiver<State>,
+    /// 请求的取消标记
+    cancelled: Arc<AtomicBool>,
 }
 
 impl Response {
@@ -32,6 +40,8 @@ impl Response {
 
 impl Drop for Response {
     fn drop(&mut self) {
+        // 响应被释放后不再需要数据，通知数据源停止执行
+        self.cancelled.store(true, Ordering::Relaxed);
         drop(&self.tx);
         drop(&self.columns);
     }
@@ -41,7 +51,18 @@ impl Statement {
     /// 创建结果集，通过最大执行时间 `timeout` 和 数据流接收器 `tx`
     #[inline(always)]
     pub fn new(timeout: Option<Duration>, tx: Receiver<State>) -> Self {
-        Self { tx, timeout }
+        Self {
+            tx,
+            timeout,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// 关联请求的取消标记，响应被释放时会取消该请求
+    #[inline(always)]
+    pub(crate) fn bind(mut self, request: &Request) -> Self {
+        self.cancelled = request.cancel_flag();
+        self
     }
 
     /// 等待数据响应，返回响应内容
@@ -57,6 +78,7 @@ impl Statement {
                 timeout: self.timeout,
                 columns,
                 tx: self.tx,
+                cancelled: self.cancelled,
             });
         } else if let State::Err(err) = state {
             return Err(err);
@@ -114,7 +136,7 @@ impl Iterator for Response {
 pub fn new_req(args: Args, timeout: Duration) -> (Request, Statement) {
     let (tx, rx) = channel();
     let request = Request::new(args, tx);
-    let statement = Statement::new(Some(timeout), rx);
+    let statement = Statement::new(Some(timeout), rx).bind(&request);
     return (request, statement);
 }
 
@@ -122,7 +144,7 @@ pub fn new_req(args: Args, timeout: Duration) -> (Request, Statement) {
 pub fn new_req_none(args: Args) -> (Request, Statement) {
     let (tx, rx) = channel();
     let request = Request::new(args, tx);
-    let statement = Statement::new(None, rx);
+    let statement = Statement::new(None, rx).bind(&request);
     return (request, statement);
 }
 
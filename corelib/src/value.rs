@@ -73,15 +73,38 @@ impl_try_from!(Integer: u16, "u16");
 impl_try_from!(Integer: u8, "u8");
 impl_try_from!(Number: f64, "f64");
 impl_try_from!(Number: f32, "f32");
-impl_try_from!(Boolean: bool, "bool");
 impl_try_from!(Bytes: Bytes, "bytes");
 
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(val: Option<T>) -> Self {
+        match val {
+            Some(val) => val.into(),
+            None => Value::Nil,
+        }
+    }
+}
+
 impl Into<Value> for () {
     fn into(self) -> Value {
         Value::Nil
     }
 }
 
+impl TryFrom<Value> for bool {
+    type Error = Error;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        // SQLite 中没有 Boolean 类型，true/false 会以整型 1/0 传入
+        match value {
+            Value::Boolean(val) => Ok(val),
+            Value::Integer(val) => Ok(val != 0),
+            _ => Err(Error::invalid_type(format!(
+                "failed to parse bool for {:?}",
+                value
+            ))),
+        }
+    }
+}
+
 impl TryFrom<Value> for String {
     type Error = Error;
     fn try_from(value: Value) -> Result<Self, Self::Error> {
@@ -215,6 +238,12 @@ fn from_value() {
     let val: Value = ().into();
     assert_eq!(Value::Nil, val);
 
+    let val: Value = Some(10).into();
+    assert_eq!(Value::Integer(10), val);
+
+    let val: Value = Option::<i64>::None.into();
+    assert_eq!(Value::Nil, val);
+
     let val: Value = vec![0x09_u8, 0x12].into();
     assert_eq!(Value::Bytes(vec![0x09_u8, 0x12]), val);
 }
@@ -241,6 +270,14 @@ fn try_from_value_failed() {
     let _: u8 = u8::try_from(Value::Number(10.02)).unwrap();
 }
 
+#[test]
+fn try_from_bool() {
+    assert!(bool::try_from(Value::Boolean(true)).unwrap());
+    assert!(bool::try_from(Value::Integer(1)).unwrap());
+    assert!(!bool::try_from(Value::Integer(0)).unwrap());
+    assert!(bool::try_from(Value::Number(1.0)).is_err());
+}
+
 #[test]
 fn get_type() {
     assert_eq!(DataType::Number, Value::from(10.0).get_type());
@@ -13,12 +13,24 @@ mod sql_test {
             r#"
             SELECT * FROM filesystem() WHERE name NOT LIKE '%tmp%'
         "#,
-            columns![String: "name", String: "mount_on", Integer: "total_bytes", Integer: "used_bytes", Integer: "free_bytes"],
+            columns![String: "name", String: "mount_on", Integer: "total_bytes", Integer: "used_bytes", Integer: "free_bytes", String: "fs_type", String: "options", Integer: "inodes_total", Integer: "inodes_used", Integer: "inodes_free", Integer: "read_only"],
             3,
             Duration::from_secs(4),
         );
     }
     #[test]
+    fn filesystem_all() {
+        init_log();
+        assert_agent_sql(
+            r#"
+            SELECT mount_on, fs_type, inodes_used * 100.0 / inodes_total as inode_usage FROM filesystem(true) WHERE inodes_total > 0
+        "#,
+            columns![String: "mount_on", String: "fs_type", Number: "inode_usage"],
+            1,
+            Duration::from_secs(4),
+        );
+    }
+    #[test]
     fn host_basic() {
         init_log();
         assert_agent_sql(
@@ -79,15 +91,206 @@ mod sql_test {
         );
     }
     #[test]
+    fn process() {
+        init_log();
+        assert_agent_sql(
+            r#"
+            SELECT pid, ppid, name, user FROM process() WHERE pid > 0
+        "#,
+            columns![Integer: "pid", Integer: "ppid", String: "name", String: "user"],
+            1,
+            Duration::from_secs(10),
+        );
+    }
+    #[test]
+    fn net_io() {
+        init_log();
+        assert_agent_sql(
+            r#"
+            SELECT * FROM net_io()
+        "#,
+            columns![String: "interface", Integer: "bytes_sent", Integer: "bytes_recv", Integer: "packets_sent", Integer: "packets_recv", Integer: "errors_sent", Integer: "errors_recv", Integer: "drop_sent", Integer: "drop_recv"],
+            1,
+            Duration::from_secs(4),
+        );
+    }
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn net_connections() {
+        init_log();
+        assert_agent_sql(
+            r#"
+            SELECT protocol, local_addr, local_port, state FROM net_connections() WHERE state = 'LISTEN'
+        "#,
+            columns![String: "protocol", String: "local_addr", Integer: "local_port", String: "state"],
+            0,
+            Duration::from_secs(4),
+        );
+    }
+    #[test]
+    fn disk_io() {
+        init_log();
+        assert_agent_sql(
+            r#"
+            SELECT device, read_iops, write_iops, util FROM disk_io(500)
+        "#,
+            columns![String: "device", Number: "read_iops", Number: "write_iops", Number: "util"],
+            0,
+            Duration::from_secs(4),
+        );
+    }
+    #[test]
+    fn cpu_times() {
+        init_log();
+        assert_agent_sql(
+            r#"
+            SELECT cpu, user, system, idle FROM cpu_times(500) WHERE cpu = 'cpu-total'
+        "#,
+            columns![String: "cpu", Number: "user", Number: "system", Number: "idle"],
+            1,
+            Duration::from_secs(4),
+        );
+    }
+    #[test]
+    #[cfg(unix)]
+    fn host_load() {
+        init_log();
+        assert_agent_sql(
+            r#"
+            SELECT load1, load5, load15, boot_time FROM host_load()
+        "#,
+            columns![Number: "load1", Number: "load5", Number: "load15", Integer: "boot_time"],
+            1,
+            Duration::from_secs(4),
+        );
+    }
+    #[test]
+    #[cfg(unix)]
+    fn host_users() {
+        init_log();
+        assert_agent_sql(
+            r#"
+            SELECT user, terminal, host, login_time FROM host_users()
+        "#,
+            columns![String: "user", String: "terminal", String: "host", Integer: "login_time"],
+            0,
+            Duration::from_secs(4),
+        );
+    }
+    #[test]
+    fn list_dir() {
+        init_log();
+        assert_agent_sql(
+            r#"
+            SELECT name, file_type, size FROM list_dir("/etc", true, 1) WHERE name = 'hosts'
+        "#,
+            columns![String: "name", String: "file_type", Integer: "size"],
+            1,
+            Duration::from_secs(4),
+        );
+    }
+    #[test]
+    fn tail_file() {
+        init_log();
+        assert_agent_sql(
+            r#"
+            SELECT line, byte_offset FROM tail_file("/etc/hosts", 0, 1)
+        "#,
+            columns![String: "line", Integer: "byte_offset"],
+            1,
+            Duration::from_secs(4),
+        );
+    }
+    #[test]
+    fn find_files() {
+        init_log();
+        assert_agent_sql(
+            r#"
+            SELECT path, size, mtime FROM find_files("/etc", "host*", 1)
+        "#,
+            columns![String: "path", Integer: "size", Integer: "mtime"],
+            1,
+            Duration::from_secs(4),
+        );
+    }
+    #[test]
+    fn file_hash() {
+        init_log();
+        assert_agent_sql(
+            r#"
+            SELECT algorithm, hash FROM file_hash("/etc/hosts", "md5")
+        "#,
+            columns![String: "algorithm", String: "hash"],
+            1,
+            Duration::from_secs(4),
+        );
+    }
+    #[test]
     fn shell() {
         init_log();
         assert_agent_sql(
             r#"
             SELECT * FROM shell("echo Hello", 10) WHERE line_num = 0
         "#,
-            columns![String: "line", Integer: "line_num"],
+            columns![String: "line", Integer: "line_num", String: "stream", Integer: "exit_code", Integer: "elapsed_ms"],
             1,
             Duration::from_secs(4),
         );
     }
+    #[test]
+    fn shell_status() {
+        init_log();
+        assert_agent_sql(
+            r#"
+            SELECT exit_code FROM shell("echo error >&2; exit 3", 10, NULL, NULL, NULL, true) WHERE stream = 'status' AND exit_code = 3
+        "#,
+            columns![Integer: "exit_code"],
+            1,
+            Duration::from_secs(4),
+        );
+    }
+    #[test]
+    fn shell_filter() {
+        init_log();
+        assert_agent_sql(
+            r#"
+            SELECT line FROM shell("seq 1 10", 10) WHERE stream = 'stdout' AND line_num >= 2 AND line_num < 5
+        "#,
+            columns![String: "line"],
+            3,
+            Duration::from_secs(4),
+        );
+    }
+    #[test]
+    fn shell_limit() {
+        init_log();
+        const MARKER: &str = "/tmp/bee_test_shell_limit";
+        let _ = std::fs::remove_file(MARKER);
+        assert_agent_sql(
+            &format!(
+                r#"
+            SELECT line FROM shell("echo first; sleep 1; touch {}", 20) LIMIT 1
+        "#,
+                MARKER
+            ),
+            columns![String: "line"],
+            1,
+            Duration::from_secs(4),
+        );
+        // 命令在 LIMIT 满足后被终止，不会再创建标记文件
+        std::thread::sleep(Duration::from_secs(2));
+        assert!(!std::path::Path::new(MARKER).exists());
+    }
+    #[test]
+    fn process_filter() {
+        init_log();
+        assert_agent_sql(
+            r#"
+            SELECT pid, name FROM process() WHERE pid = 1
+        "#,
+            columns![Integer: "pid", String: "name"],
+            1,
+            Duration::from_secs(10),
+        );
+    }
 }
@@ -140,4 +140,56 @@ mod test {
             Duration::from_secs(4),
         );
     }
+
+    #[test]
+    fn test_list_dir() {
+        init_log();
+        assert_remote_sql(
+            r#"
+            SELECT name, file_type, size FROM list_dir('/etc') WHERE name = 'hosts'
+        "#,
+            columns![String: "name", String: "file_type", Integer: "size"],
+            1,
+            Duration::from_secs(4),
+        );
+    }
+
+    #[test]
+    fn test_tail_file() {
+        init_log();
+        assert_remote_sql(
+            r#"
+            SELECT line, byte_offset FROM tail_file('/etc/hosts', 0, 1)
+        "#,
+            columns![String: "line", Integer: "byte_offset"],
+            1,
+            Duration::from_secs(4),
+        );
+    }
+
+    #[test]
+    fn test_find_files() {
+        init_log();
+        assert_remote_sql(
+            r#"
+            SELECT path, size, mtime FROM find_files('/etc', 'host*', 1)
+        "#,
+            columns![String: "path", Integer: "size", Integer: "mtime"],
+            1,
+            Duration::from_secs(4),
+        );
+    }
+
+    #[test]
+    fn test_file_hash() {
+        init_log();
+        assert_remote_sql(
+            r#"
+            SELECT algorithm, hash FROM file_hash('/etc/hosts', 'md5')
+        "#,
+            columns![String: "algorithm", String: "hash"],
+            1,
+            Duration::from_secs(4),
+        );
+    }
 }
@@ -435,8 +435,11 @@ async fn new_statement<'a>(
         "[{}-{}] process {} in {} s.", app_name, req.id, req.script, req.timeout
     );
 
-    let statement =
-        connection.new_statement(&req.script, Duration::from_secs(req.timeout as u64))?;
+    let statement = connection.new_statement_with_args(
+        &req.script,
+        req.args.clone(),
+        Duration::from_secs(req.timeout as u64),
+    )?;
     let response = statement.wait()?;
     let columns = response.columns();
 